@@ -0,0 +1,209 @@
+//! Solution certificate module
+//!
+//! Lets a teacher check that a round was really played:
+//! - A one-line certificate of the seed, range, secret, guesses and outcome
+//! - A checksum over those fields, so an edited certificate is caught
+//! - Verification that also checks the guesses fit the range and outcome
+//!
+//! The checksum only catches edits to a certificate the game printed. Its
+//! key is in this public source, so anyone who reads it can compute the
+//! checksum for an invented game: a certificate is evidence, not proof.
+
+use std::fmt;
+use crate::utils::format_number;
+
+/// Layout version written at the front of every certificate
+const CERTIFICATE_VERSION: &str = "GG1";
+/// Mixed into the checksum (public, so it stops edits but not forgeries)
+const CHECKSUM_KEY: &str = "fantastic-number-guessing-game";
+
+/// Everything a certificate records about one round
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameSummary {
+    /// Session seed the round was played under
+    pub seed: u64,
+    pub range: (f64, f64),
+    pub secret: f64,
+    /// How far off a guess could be and still win
+    pub tolerance: f64,
+    /// Every guess, in order
+    pub guesses: Vec<f64>,
+    pub won: bool,
+}
+
+impl fmt::Display for GameSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let guesses: Vec<String> = self.guesses.iter().map(|&guess| format_number(guess)).collect();
+        write!(
+            f,
+            "Seed {}, range {}–{}, secret {}: {} after {} guess(es) ({})",
+            self.seed,
+            format_number(self.range.0),
+            format_number(self.range.1),
+            format_number(self.secret),
+            if self.won { "won" } else { "lost" },
+            self.guesses.len(),
+            guesses.join(", ")
+        )
+    }
+}
+
+/// Why a certificate was rejected
+#[derive(Debug, PartialEq)]
+pub enum VerifyError {
+    /// Not laid out like a certificate
+    Malformed(String),
+    /// The checksum doesn't match the fields, so something was edited
+    Tampered,
+    /// The fields contradict each other (e.g. a win with no guess on the secret)
+    Inconsistent(String),
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::Malformed(reason) => write!(f, "Not a valid certificate: {}", reason),
+            VerifyError::Tampered => write!(f, "Checksum mismatch: the certificate was altered."),
+            VerifyError::Inconsistent(reason) => write!(f, "The certificate doesn't add up: {}", reason),
+        }
+    }
+}
+
+/// Encodes a round as a certificate string
+/// Layout: `GG1:SEED:START..END:SECRET:TOLERANCE:GUESS,GUESS,...:won|lost:CHECKSUM`
+pub fn make_certificate(summary: &GameSummary) -> String {
+    let guesses: Vec<String> = summary.guesses.iter().map(f64::to_string).collect();
+    let body = format!(
+        "{}:{}:{}..{}:{}:{}:{}:{}",
+        CERTIFICATE_VERSION,
+        summary.seed,
+        summary.range.0,
+        summary.range.1,
+        summary.secret,
+        summary.tolerance,
+        guesses.join(","),
+        if summary.won { "won" } else { "lost" }
+    );
+    format!("{}:{:016x}", body, checksum(&body))
+}
+
+/// Checks a certificate and decodes the round it describes
+/// Besides the checksum, a win must end on the first guess that hits the
+/// secret, and a loss must never hit it
+/// Returns:
+///   The round, or why the certificate can't be trusted
+pub fn verify_certificate(s: &str) -> Result<GameSummary, VerifyError> {
+    let s = s.trim();
+    let (body, sum) = s.rsplit_once(':').ok_or_else(|| VerifyError::Malformed(String::from("no checksum")))?;
+    if u64::from_str_radix(sum, 16).ok() != Some(checksum(body)) {
+        return Err(VerifyError::Tampered);
+    }
+
+    let fields: Vec<&str> = body.split(':').collect();
+    let [version, seed, range, secret, tolerance, guesses, outcome] = fields[..] else {
+        return Err(VerifyError::Malformed(format!("expected 8 fields, found {}", fields.len() + 1)));
+    };
+    if version != CERTIFICATE_VERSION {
+        return Err(VerifyError::Malformed(format!("unknown version '{}'", version)));
+    }
+    let seed = seed.parse().map_err(|_| VerifyError::Malformed(format!("bad seed '{}'", seed)))?;
+    let (start, end) = range.split_once("..").ok_or_else(|| VerifyError::Malformed(format!("bad range '{}'", range)))?;
+    let range = (number(start)?, number(end)?);
+    let secret = number(secret)?;
+    let tolerance = number(tolerance)?;
+    let guesses = if guesses.is_empty() {
+        Vec::new()
+    } else {
+        guesses.split(',').map(number).collect::<Result<Vec<f64>, VerifyError>>()?
+    };
+    let won = match outcome {
+        "won" => true,
+        "lost" => false,
+        other => return Err(VerifyError::Malformed(format!("bad outcome '{}'", other))),
+    };
+
+    if range.0 > range.1 {
+        return Err(VerifyError::Inconsistent(String::from("the range is backwards")));
+    }
+    let inside = |value: f64| (range.0..=range.1).contains(&value);
+    if !inside(secret) {
+        return Err(VerifyError::Inconsistent(String::from("the secret is outside the range")));
+    }
+    if let Some(&guess) = guesses.iter().find(|&&guess| !inside(guess)) {
+        return Err(VerifyError::Inconsistent(format!("guess {} is outside the range", format_number(guess))));
+    }
+    if tolerance < 0.0 {
+        return Err(VerifyError::Inconsistent(String::from("the tolerance is negative")));
+    }
+    let first_hit = guesses.iter().position(|&guess| (guess - secret).abs() <= tolerance);
+    match (won, first_hit) {
+        (true, None) => return Err(VerifyError::Inconsistent(String::from("a win needs a guess on the secret"))),
+        (true, Some(hit)) if hit + 1 != guesses.len() => {
+            return Err(VerifyError::Inconsistent(String::from("guesses continue after the winning one")));
+        },
+        (false, Some(_)) => return Err(VerifyError::Inconsistent(String::from("a lost game has a guess on the secret"))),
+        _ => {},
+    }
+    Ok(GameSummary { seed, range, secret, tolerance, guesses, won })
+}
+
+/// Parses one numeric certificate field
+fn number(field: &str) -> Result<f64, VerifyError> {
+    match field.parse::<f64>() {
+        Ok(n) if n.is_finite() => Ok(n),
+        _ => Err(VerifyError::Malformed(format!("bad number '{}'", field))),
+    }
+}
+
+/// 64-bit FNV-1a hash of the key followed by the certificate body
+fn checksum(body: &str) -> u64 {
+    CHECKSUM_KEY.bytes().chain(body.bytes()).fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(guesses: &[f64], won: bool) -> GameSummary {
+        GameSummary { seed: 7, range: (-50.0, 50.0), secret: 12.5, tolerance: 0.5, guesses: guesses.to_vec(), won }
+    }
+
+    /// A certificate with a freshly computed checksum, as a forger could make
+    fn forged(body: &str) -> String {
+        format!("{}:{:016x}", body, checksum(body))
+    }
+
+    #[test]
+    fn certificate_round_trips() {
+        for game in [summary(&[0.0, 25.0, 12.2], true), summary(&[-3.0, 40.0], false), summary(&[], false)] {
+            assert_eq!(verify_certificate(&make_certificate(&game)), Ok(game));
+        }
+    }
+
+    #[test]
+    fn edited_certificate_fails_the_checksum() {
+        let certificate = make_certificate(&summary(&[0.0, 12.5], true));
+        let edited = certificate.replacen(":0,12.5:", ":12.5:", 1);
+        assert_ne!(edited, certificate);
+        assert_eq!(verify_certificate(&edited), Err(VerifyError::Tampered));
+        assert!(matches!(verify_certificate("GG1:7"), Err(VerifyError::Tampered)));
+    }
+
+    #[test]
+    fn forged_outcomes_are_inconsistent() {
+        let no_hit = forged("GG1:7:-50..50:12.5:0.5:0,30:won");
+        let after_win = forged("GG1:7:-50..50:12.5:0.5:12.5,30:won");
+        let lost_hit = forged("GG1:7:-50..50:12.5:0.5:3,12.5:lost");
+        for certificate in [no_hit, after_win, lost_hit] {
+            assert!(matches!(verify_certificate(&certificate), Err(VerifyError::Inconsistent(_))), "{}", certificate);
+        }
+    }
+
+    #[test]
+    fn guesses_outside_the_range_are_inconsistent() {
+        let certificate = forged("GG1:7:-50..50:12.5:0.5:99,12.5:won");
+        assert!(matches!(verify_certificate(&certificate), Err(VerifyError::Inconsistent(_))));
+    }
+}
@@ -0,0 +1,1477 @@
+//! Game configuration module
+//!
+//! Holds the settings that shape a play session:
+//! - Player identity
+//! - Optional features toggled from the command line
+//! - Command-line and config-file parsing
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use crate::error::GameError;
+use crate::history::{HistoryFilter, parse_date};
+use crate::menu::validate_hidden_item;
+use crate::simulate::{SimPlayer, SimulationPlan};
+
+/// Default cap on `end - start` for any guessing range
+pub const DEFAULT_MAX_SPAN: f64 = 1e9;
+
+/// Whether the secret (and guesses) are whole numbers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameMode {
+    Decimal,
+    Integer,
+}
+
+/// Default guess prompt; `{range}` is replaced with the guessing range
+pub const DEFAULT_PROMPT: &str = "Enter your guess ({range}): ";
+
+/// Preset difficulty levels; each sets a default guessing range
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Insane,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard, Difficulty::Insane];
+
+    /// Lowercase name used in flags and save files
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Insane => "insane",
+        }
+    }
+
+    /// Parses a difficulty name (case-insensitive)
+    pub fn from_name(name: &str) -> Option<Difficulty> {
+        Difficulty::ALL.into_iter().find(|difficulty| difficulty.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// The next harder difficulty (Insane stays Insane)
+    pub fn harder(&self) -> Difficulty {
+        match self {
+            Difficulty::Easy => Difficulty::Medium,
+            Difficulty::Medium => Difficulty::Hard,
+            Difficulty::Hard | Difficulty::Insane => Difficulty::Insane,
+        }
+    }
+
+    /// The next easier difficulty (Easy stays Easy)
+    pub fn easier(&self) -> Difficulty {
+        match self {
+            Difficulty::Easy | Difficulty::Medium => Difficulty::Easy,
+            Difficulty::Hard => Difficulty::Medium,
+            Difficulty::Insane => Difficulty::Hard,
+        }
+    }
+
+    /// Default guessing range for this difficulty
+    pub fn range(&self) -> (f64, f64) {
+        match self {
+            Difficulty::Easy => (1.0, 50.0),
+            Difficulty::Medium => (1.0, 100.0),
+            Difficulty::Hard => (1.0, 500.0),
+            Difficulty::Insane => (1.0, 1000.0),
+        }
+    }
+
+    /// Whether this difficulty is still locked
+    /// Insane opens up once enough games have been won on Hard
+    /// Parameters:
+    ///   hard_wins: u32 - all-time wins on Hard
+    ///   unlock_wins: u32 - Hard wins needed to unlock Insane
+    pub fn locked(&self, hard_wins: u32, unlock_wins: u32) -> bool {
+        *self == Difficulty::Insane && hard_wins < unlock_wins
+    }
+
+    /// Name for the difficulty menu, saying what unlocks it if locked
+    /// e.g. "Insane (locked — win 3 on Hard first)"
+    pub fn menu_label(&self, hard_wins: u32, unlock_wins: u32) -> String {
+        if self.locked(hard_wins, unlock_wins) {
+            format!("{} (locked — win {} on {} first)", self, unlock_wins, Difficulty::Hard)
+        } else {
+            self.to_string()
+        }
+    }
+}
+
+impl std::fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = self.name();
+        write!(f, "{}{}", name[..1].to_uppercase(), &name[1..])
+    }
+}
+
+/// Groups of hints that can each be given their own per-round allowance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HintCategory {
+    /// Simple arithmetic hints
+    Easy,
+    /// Complex equation hints
+    Hard,
+    /// Hints about where the secret sits (perfect-play, quartile)
+    Proximity,
+}
+
+impl HintCategory {
+    pub const ALL: [HintCategory; 3] = [HintCategory::Easy, HintCategory::Hard, HintCategory::Proximity];
+
+    /// Lowercase name used in flags
+    pub fn name(&self) -> &'static str {
+        match self {
+            HintCategory::Easy => "easy",
+            HintCategory::Hard => "hard",
+            HintCategory::Proximity => "proximity",
+        }
+    }
+
+    /// Parses a category name (case-insensitive)
+    pub fn from_name(name: &str) -> Option<HintCategory> {
+        HintCategory::ALL.into_iter().find(|category| category.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// How much a hint from this category gives away, shown in the hint menu
+    pub fn power(&self) -> &'static str {
+        match self {
+            HintCategory::Easy => "reveals ~1 easy equation",
+            HintCategory::Hard => "reveals ~1 hard equation",
+            HintCategory::Proximity => "narrows the range",
+        }
+    }
+}
+
+/// How the secret is spread over the range
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Distribution {
+    /// Every value equally likely
+    Uniform,
+    /// Values near the middle of the range more likely
+    CenterWeighted,
+    /// Values near either end of the range more likely
+    EdgeWeighted,
+    /// The sum of a roll of dice (set with `--dice`)
+    Dice(DiceRange),
+}
+
+impl Distribution {
+    pub const ALL: [Distribution; 3] = [Distribution::Uniform, Distribution::CenterWeighted, Distribution::EdgeWeighted];
+
+    /// Lowercase name used in flags
+    pub fn name(&self) -> &'static str {
+        match self {
+            Distribution::Uniform => "uniform",
+            Distribution::CenterWeighted => "center",
+            Distribution::EdgeWeighted => "edges",
+            Distribution::Dice(_) => "dice",
+        }
+    }
+
+    /// Parses a distribution name (case-insensitive)
+    pub fn from_name(name: &str) -> Option<Distribution> {
+        Distribution::ALL.into_iter().find(|distribution| distribution.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// What happens when a guess repeats one already made this round
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RepeatGuesses {
+    /// Warn, then count the guess
+    Warn,
+    /// Warn and ask before counting the guess (just warn without menus)
+    Confirm,
+    /// Warn and ask for another guess
+    Reject,
+}
+
+impl RepeatGuesses {
+    pub const ALL: [RepeatGuesses; 3] = [RepeatGuesses::Warn, RepeatGuesses::Confirm, RepeatGuesses::Reject];
+
+    /// Lowercase name used in flags
+    pub fn name(&self) -> &'static str {
+        match self {
+            RepeatGuesses::Warn => "warn",
+            RepeatGuesses::Confirm => "confirm",
+            RepeatGuesses::Reject => "reject",
+        }
+    }
+
+    /// Parses a policy name (case-insensitive)
+    pub fn from_name(name: &str) -> Option<RepeatGuesses> {
+        RepeatGuesses::ALL.into_iter().find(|policy| policy.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// How much of the secret is shown when a timed round runs out
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeoutReveal {
+    /// Show nothing
+    Nothing,
+    /// Show which quarter of the range held it
+    Quartile,
+    /// Show the secret itself
+    Secret,
+}
+
+impl TimeoutReveal {
+    pub const ALL: [TimeoutReveal; 3] = [TimeoutReveal::Nothing, TimeoutReveal::Quartile, TimeoutReveal::Secret];
+
+    /// Lowercase name used in flags
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimeoutReveal::Nothing => "none",
+            TimeoutReveal::Quartile => "quartile",
+            TimeoutReveal::Secret => "secret",
+        }
+    }
+
+    /// Parses a reveal name (case-insensitive)
+    pub fn from_name(name: &str) -> Option<TimeoutReveal> {
+        TimeoutReveal::ALL.into_iter().find(|reveal| reveal.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// Dice rolled for the secret, e.g. 3d6 (three six-sided dice)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiceRange {
+    pub count: u32,
+    pub sides: u32,
+}
+
+impl DiceRange {
+    /// Lowest possible sum (every die shows 1)
+    pub fn min(&self) -> f64 {
+        self.count as f64
+    }
+
+    /// Highest possible sum (every die shows its top face)
+    pub fn max(&self) -> f64 {
+        self.count as f64 * self.sides as f64
+    }
+}
+
+impl fmt::Display for DiceRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}d{}", self.count, self.sides)
+    }
+}
+
+/// Parses dice notation such as "3d6" (the count may be left out: "d20")
+/// Returns:
+///   The dice, or a message saying what is wrong
+pub fn parse_dice(spec: &str) -> Result<DiceRange, String> {
+    let invalid = || format!("Invalid dice '{}' (expected NdS, e.g. 3d6, with 1-100 dice of 2-1000 sides)", spec);
+    let lower = spec.trim().to_ascii_lowercase();
+    let Some((count, sides)) = lower.split_once('d') else {
+        return Err(invalid());
+    };
+    let count = if count.is_empty() { Ok(1) } else { count.parse::<u32>() };
+    match (count, sides.parse::<u32>()) {
+        (Ok(count), Ok(sides)) if (1..=100).contains(&count) && (2..=1000).contains(&sides) => Ok(DiceRange { count, sides }),
+        _ => Err(invalid()),
+    }
+}
+
+/// Environment variable that seeds the game when `--seed` is not given
+pub const SEED_ENV: &str = "GUESSINGGAME_SEED";
+
+/// Named quick ranges: (name, start, end, mode)
+const RANGE_PRESETS: [(&str, f64, f64, GameMode); 3] = [
+    ("dice", 1.0, 6.0, GameMode::Integer),
+    ("percent", 0.0, 100.0, GameMode::Integer),
+    ("clock", 1.0, 12.0, GameMode::Integer),
+];
+
+/// What the program was asked to do
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Play the game (the default)
+    Play,
+    /// `stats merge FILE`: fold another stats file into the local one
+    StatsMerge(PathBuf),
+    /// `compare --seed-a X --seed-b Y`: auto-solve the first secret of two seeds
+    Compare { seed_a: u64, seed_b: u64 },
+    /// `trainer`: practice solving hard-hint equations for S
+    Trainer,
+    /// `history [--won] [--difficulty D] [--since DATE] [--limit N]`: list past games
+    History(HistoryFilter),
+    /// `simulate [--games N] [--random] [--cap N]`: play many headless games and report
+    Simulate(SimulationPlan),
+    /// `weaknesses`: average attempts by where in the range the secret was
+    Weaknesses,
+    /// `verify CERTIFICATE`: check a solution certificate and show its round
+    Verify(String),
+}
+
+/// All settings for a play session
+#[derive(Clone)]
+pub struct GameConfig {
+    /// Name shown in exported stats
+    pub player: String,
+    /// Where to write a markdown stats table on exit (if anywhere)
+    pub export_md: Option<PathBuf>,
+    /// Where to write the all-time guess heatmap as CSV on exit (if anywhere)
+    pub export_heatmap: Option<PathBuf>,
+    /// Print "So close!" when a wrong guess lands within this distance
+    pub alert_distance: Option<f64>,
+    /// Lower bound of the default guessing range
+    pub range_start: f64,
+    /// Upper bound of the default guessing range
+    pub range_end: f64,
+    /// Widest range allowed, guarding against pathological spans
+    pub max_span: f64,
+    /// How far off a guess may be and still win (decimal mode)
+    pub tolerance: f64,
+    /// Personal goal: win within this many attempts
+    pub target: Option<u32>,
+    /// A round stops with an error after this many attempts
+    pub attempt_ceiling: u32,
+    /// Quit (after a warning) when no input arrives for this long
+    pub idle_timeout: Option<Duration>,
+    /// How long the idle warning waits for input before quitting
+    pub idle_grace: Duration,
+    /// Longest input line accepted, in bytes; longer lines are discarded
+    pub max_line: usize,
+    /// Offer a free half-range hint after repeated far-off guesses
+    pub mercy: bool,
+    /// Decimal or whole-number secrets
+    pub mode: GameMode,
+    /// Whether a flag chose the mode; otherwise it is inferred from the bounds
+    pub explicit_mode: bool,
+    /// Seed for reproducible secrets (random when not given)
+    pub seed: Option<u64>,
+    /// Also print one machine-readable RESULT line per round
+    pub oneline: bool,
+    /// Play a single non-interactive round with guesses read from this file
+    pub guesses_from: Option<PathBuf>,
+    /// Guess prompt template, may contain `{range}`
+    pub prompt: String,
+    /// Time allowed per round before it is lost
+    pub time_limit: Option<Duration>,
+    /// What to show about the secret when the time limit runs out
+    pub timeout_reveal: TimeoutReveal,
+    /// Hints allowed per round (None = unlimited)
+    pub hint_budget: Option<u32>,
+    /// Points per secret; wrong guesses cost more the farther off they are
+    /// and the round is lost at zero (None = no point budget)
+    pub point_budget: Option<u32>,
+    /// Only pick secrets that are multiples of this number
+    pub multiple_of: Option<u32>,
+    /// Apply a random rule twist to every new game
+    pub surprise: bool,
+    /// Print the resolved settings and exit without playing
+    pub print_config: bool,
+    /// Print every easy and hard hint worked out for `dump_secret`, then exit
+    pub dump_hints: bool,
+    /// Secret used by `--dump-hints` (None = middle of the range)
+    pub dump_secret: Option<f64>,
+    /// Difficulty preset; also keys the scoreboard
+    pub difficulty: Difficulty,
+    /// Where the scoreboard and other save files live
+    pub data_dir: PathBuf,
+    /// Whether "try same number again" keeps counting attempts (the
+    /// default) or starts the tally over from 0
+    pub carry_attempts: bool,
+    /// Subcommand to run instead of (or as) the game
+    pub command: Command,
+    /// Ask for each game's secret (typed hidden) instead of picking one
+    pub set_secret: bool,
+    /// Nudge the difficulty up or down based on recent results
+    pub adaptive: bool,
+    /// Halve the range around a random center after each win (reset on a loss)
+    pub escalating: bool,
+    /// Ring the terminal bell on a win (only when stdout is a terminal, never in quiet mode)
+    pub bell: bool,
+    /// Print every guess with its verdict and timing after each round
+    pub show_trace: bool,
+    /// Only ever say higher or lower: no closeness, alerts or hints
+    pub blind: bool,
+    /// Retry-menu options left out (by name, e.g. "range")
+    pub hidden_retry: Vec<String>,
+    /// Leave the range start itself out (start < x)
+    pub exclusive_min: bool,
+    /// Leave the range end itself out (x < end)
+    pub exclusive_max: bool,
+    /// Per-round allowance for each hint category (missing = unlimited)
+    pub hint_limits: HashMap<HintCategory, u32>,
+    /// Draw hints without replacement from a seeded, shuffled deck each round
+    pub hint_deck: bool,
+    /// Offer to reveal the secret once this many attempts were spent on it
+    pub reveal_after: Option<u32>,
+    /// All-time wins on Hard needed before Insane can be played
+    pub unlock_wins: u32,
+    /// Read the range from the first stdin line and guesses from the rest
+    pub stdin_range: bool,
+    /// Winning when the secret is this number earns a bonus
+    pub lucky: Option<f64>,
+    /// Keep a range chosen mid-session for later games instead of resetting it
+    pub sticky_range: bool,
+    /// Ranges to pick from at random for each new game (empty = just the main range)
+    pub bands: Vec<GameRange>,
+    /// Leave out the "Attempt #N" header and spacing between guesses
+    pub compact: bool,
+    /// After a hard hint, show how to rearrange it for the secret
+    pub explain_hints: bool,
+    /// Multiplier for easy and hard hint values, for display only
+    pub hint_scale: f64,
+    /// Largest hard-hint value worth showing; bigger ones are re-picked
+    pub max_hint_value: f64,
+    /// How the secret is spread over the range
+    pub distribution: Distribution,
+    /// Skip the all-time stats banner on launch
+    pub quiet: bool,
+    /// Show menus and prompts (None = only when stdin is a terminal)
+    pub interactive: Option<bool>,
+    /// Give proximity feedback (color, close calls, closest guess) only
+    /// every this many attempts; other guesses just get a direction
+    pub proximity_interval: u32,
+    /// Point out guesses that earlier guesses already ruled out
+    pub nudge: bool,
+    /// Watch the auto-solver find the first secret instead of playing
+    pub demo: bool,
+    /// Pause between the demo's steps
+    pub animate: bool,
+    /// Explain the reasoning behind each demo step
+    pub narrate: bool,
+    /// How long each animated demo step stays on screen (zero also skips
+    /// the pauses between celebration frames)
+    pub demo_delay: Duration,
+    /// Play a confetti animation on a win
+    pub celebrate: bool,
+    /// Print a solution certificate (for `verify`) after each round
+    pub certificate: bool,
+    /// Round decimal secrets to this many places, so exact guesses can win
+    pub secret_decimals: Option<usize>,
+    /// Accept arithmetic such as "1/3" or "50+5" as a guess
+    pub expr_input: bool,
+    /// How a repeat of an earlier guess this round is handled
+    pub repeat_guesses: RepeatGuesses,
+    /// Only say whether a guess is warmer or colder than the last, never
+    /// which way the secret lies
+    pub proximity_only: bool,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig {
+            player: String::from("Player"),
+            export_md: None,
+            export_heatmap: None,
+            alert_distance: None,
+            range_start: 1.0,
+            range_end: 100.0,
+            max_span: DEFAULT_MAX_SPAN,
+            tolerance: 0.0,
+            target: None,
+            attempt_ceiling: 1_000_000,
+            idle_timeout: None,
+            idle_grace: Duration::from_secs(10),
+            max_line: 1024,
+            mercy: false,
+            mode: GameMode::Decimal,
+            explicit_mode: false,
+            seed: None,
+            oneline: false,
+            guesses_from: None,
+            prompt: String::from(DEFAULT_PROMPT),
+            time_limit: None,
+            timeout_reveal: TimeoutReveal::Secret,
+            hint_budget: None,
+            point_budget: None,
+            multiple_of: None,
+            surprise: false,
+            print_config: false,
+            dump_hints: false,
+            dump_secret: None,
+            difficulty: Difficulty::Medium,
+            data_dir: default_data_dir(),
+            carry_attempts: true,
+            command: Command::Play,
+            set_secret: false,
+            adaptive: false,
+            escalating: false,
+            bell: false,
+            show_trace: false,
+            blind: false,
+            hidden_retry: Vec::new(),
+            exclusive_min: false,
+            exclusive_max: false,
+            hint_limits: HashMap::new(),
+            hint_deck: false,
+            reveal_after: None,
+            unlock_wins: 3,
+            stdin_range: false,
+            lucky: None,
+            sticky_range: false,
+            bands: Vec::new(),
+            compact: false,
+            explain_hints: false,
+            hint_scale: 1.0,
+            max_hint_value: 1e6,
+            distribution: Distribution::Uniform,
+            quiet: false,
+            interactive: None,
+            proximity_interval: 1,
+            nudge: true,
+            demo: false,
+            animate: false,
+            narrate: false,
+            demo_delay: Duration::from_millis(500),
+            celebrate: false,
+            certificate: false,
+            secret_decimals: None,
+            expr_input: false,
+            repeat_guesses: RepeatGuesses::Confirm,
+            proximity_only: false,
+        }
+    }
+}
+
+/// Builds a config from command-line arguments
+/// A `--config FILE` argument loads settings from that file first,
+/// so anything given on the command line overrides the file
+/// Parameters:
+///   args: iterator of arguments (without the program name)
+/// Returns:
+///   The parsed config, or an error describing the bad argument
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<GameConfig, GameError> {
+    resolve_config(args).map_err(GameError::ParseConfig)
+}
+
+/// Does the work of `parse_args`, reporting problems as plain messages
+fn resolve_config<I: IntoIterator<Item = String>>(args: I) -> Result<GameConfig, String> {
+    let mut args: Vec<String> = args.into_iter().collect();
+    let mut config = GameConfig { command: parse_command(&mut args)?, ..GameConfig::default() };
+
+    if let Some(path) = config_file_path(&args)? {
+        let file_args = read_config_file(&path)?;
+        apply_flags(&mut config, file_args)?;
+    }
+    apply_flags(&mut config, args)?;
+
+    validate_range(config.range_start, config.range_end, config.max_span)?;
+    for band in &config.bands {
+        validate_range(band.start, band.end, config.max_span)?;
+    }
+    if !config.explicit_mode {
+        config.mode = infer_mode(config.range_start, config.range_end);
+    }
+    validate_tolerance(config.tolerance, config.range_start, config.range_end)?;
+    if config.stdin_range && config.guesses_from.is_some() {
+        return Err(String::from("--stdin-range cannot be combined with --guesses-from"));
+    }
+    if config.blind && config.mercy {
+        return Err(String::from("--blind cannot be combined with --mercy"));
+    }
+    if matches!(config.distribution, Distribution::Dice(_)) && config.multiple_of.is_some() {
+        return Err(String::from("--dice cannot be combined with --multiple-of"));
+    }
+    if config.blind && config.proximity_only {
+        return Err(String::from("--blind cannot be combined with --proximity-only"));
+    }
+    if config.blind && config.alert_distance.is_some() {
+        return Err(String::from("--blind cannot be combined with --alert-distance"));
+    }
+    // Point costs grow with the distance, which would give away closeness
+    if config.blind && config.point_budget.is_some() {
+        return Err(String::from("--blind cannot be combined with --point-budget"));
+    }
+    Ok(config)
+}
+
+/// Takes a leading subcommand (like `stats merge FILE`) off the arguments
+fn parse_command(args: &mut Vec<String>) -> Result<Command, String> {
+    match args.first().map(String::as_str) {
+        Some("stats") => {
+            let words: Vec<String> = args.drain(..args.len().min(3)).collect();
+            match (words.get(1).map(String::as_str), words.get(2)) {
+                (Some("merge"), Some(path)) => Ok(Command::StatsMerge(PathBuf::from(path))),
+                (Some("merge"), None) => Err(String::from("Usage: stats merge FILE")),
+                _ => Err(String::from("Unknown stats command. Available: stats merge FILE")),
+            }
+        },
+        Some("compare") => {
+            args.remove(0);
+            let usage = "Usage: compare --seed-a X --seed-b Y [--range START..END]";
+            let seed_a = take_flag_value(args, "--seed-a").ok_or(usage)?;
+            let seed_b = take_flag_value(args, "--seed-b").ok_or(usage)?;
+            Ok(Command::Compare {
+                seed_a: parse_whole(&seed_a, "--seed-a")?,
+                seed_b: parse_whole(&seed_b, "--seed-b")?,
+            })
+        },
+        Some("trainer") => {
+            args.remove(0);
+            Ok(Command::Trainer)
+        },
+        Some("history") => {
+            args.remove(0);
+            let mut filter = HistoryFilter { won_only: take_switch(args, "--won"), ..HistoryFilter::default() };
+            if let Some(value) = take_flag_value(args, "--difficulty") {
+                filter.difficulty = Some(
+                    Difficulty::from_name(&value)
+                        .ok_or_else(|| format!("Unknown difficulty '{}'. Valid: easy, medium, hard, insane", value))?,
+                );
+            }
+            if let Some(value) = take_flag_value(args, "--since") {
+                filter.since = Some(parse_date(&value)?);
+            }
+            if let Some(value) = take_flag_value(args, "--limit") {
+                filter.limit = Some(parse_whole(&value, "--limit")?);
+            }
+            Ok(Command::History(filter))
+        },
+        Some("simulate") => {
+            args.remove(0);
+            let player = if take_switch(args, "--random") { SimPlayer::Random } else { SimPlayer::Solver };
+            let mut plan = SimulationPlan { games: 1000, player, cap: 10 };
+            if let Some(value) = take_flag_value(args, "--games") {
+                plan.games = parse_whole(&value, "--games")?;
+            }
+            if let Some(value) = take_flag_value(args, "--cap") {
+                plan.cap = parse_whole(&value, "--cap")?;
+            }
+            Ok(Command::Simulate(plan))
+        },
+        Some("weaknesses") => {
+            args.remove(0);
+            Ok(Command::Weaknesses)
+        },
+        Some("verify") => {
+            let words: Vec<String> = args.drain(..args.len().min(2)).collect();
+            match words.get(1) {
+                Some(certificate) => Ok(Command::Verify(certificate.clone())),
+                None => Err(String::from("Usage: verify CERTIFICATE")),
+            }
+        },
+        _ => Ok(Command::Play),
+    }
+}
+
+/// Removes a bare `--flag` from the arguments
+/// Returns:
+///   Whether the flag was present
+fn take_switch(args: &mut Vec<String>, flag: &str) -> bool {
+    match args.iter().position(|arg| arg == flag) {
+        Some(index) => {
+            args.remove(index);
+            true
+        },
+        None => false,
+    }
+}
+
+/// Removes a `--flag VALUE` pair from the arguments
+/// Returns:
+///   The value, or None when the flag (or its value) is missing
+fn take_flag_value(args: &mut Vec<String>, flag: &str) -> Option<String> {
+    let index = args.iter().position(|arg| arg == flag)?;
+    if index + 1 >= args.len() {
+        return None;
+    }
+    args.remove(index);
+    Some(args.remove(index))
+}
+
+/// Applies a list of flags (and their values) to a config
+fn apply_flags(config: &mut GameConfig, args: Vec<String>) -> Result<(), String> {
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--config" => { next_value(&mut args, &arg)?; }, // Already loaded first
+            "--print-config" => config.print_config = true,
+            "--dump-hints" => config.dump_hints = true,
+            "--secret" => config.dump_secret = Some(parse_number(&next_value(&mut args, &arg)?, &arg)?),
+            "--player" => config.player = next_value(&mut args, &arg)?,
+            "--export-md" => config.export_md = Some(PathBuf::from(next_value(&mut args, &arg)?)),
+            "--export-heatmap" => config.export_heatmap = Some(PathBuf::from(next_value(&mut args, &arg)?)),
+            "--alert-distance" => config.alert_distance = Some(parse_positive(&next_value(&mut args, &arg)?, &arg)?),
+            "--min" => config.range_start = parse_number(&next_value(&mut args, &arg)?, &arg)?,
+            "--max" => config.range_end = parse_number(&next_value(&mut args, &arg)?, &arg)?,
+            "--range" => (config.range_start, config.range_end) = parse_span(&next_value(&mut args, &arg)?, &arg)?,
+            "--range-from" => (config.range_start, config.range_end) = parse_examples(&next_value(&mut args, &arg)?, &arg)?,
+            "--mirror" => {
+                // Just builds [-N, N]; validation and mode inference treat it like --range
+                let half = parse_positive(&next_value(&mut args, &arg)?, &arg)?;
+                (config.range_start, config.range_end) = (-half, half);
+            },
+            "--band" => {
+                let (start, end) = parse_span(&next_value(&mut args, &arg)?, &arg)?;
+                config.bands.push(GameRange { start, end, start_inclusive: true, end_inclusive: true });
+            },
+            "--max-span" => config.max_span = parse_positive(&next_value(&mut args, &arg)?, &arg)?,
+            "--tolerance" => config.tolerance = parse_number(&next_value(&mut args, &arg)?, &arg)?,
+            "--target" => {
+                let value = next_value(&mut args, &arg)?;
+                match value.trim().parse::<u32>() {
+                    Ok(target) if target > 0 => config.target = Some(target),
+                    _ => return Err(format!("{} must be a positive whole number, got {}", arg, value)),
+                }
+            },
+            "--idle-timeout" => {
+                let seconds = parse_positive(&next_value(&mut args, &arg)?, &arg)?;
+                config.idle_timeout = Some(Duration::from_secs_f64(seconds));
+            },
+            "--idle-grace" => {
+                let seconds = parse_positive(&next_value(&mut args, &arg)?, &arg)?;
+                config.idle_grace = Duration::from_secs_f64(seconds);
+            },
+            "--demo" => config.demo = true,
+            "--animate" => config.animate = true,
+            "--narrate" => config.narrate = true,
+            "--demo-delay" => config.demo_delay = Duration::from_millis(parse_whole(&next_value(&mut args, &arg)?, &arg)?),
+            "--no-delay" => config.demo_delay = Duration::ZERO,
+            "--celebrate" => config.celebrate = true,
+            "--certificate" => config.certificate = true,
+            "--attempt-ceiling" => {
+                let value = next_value(&mut args, &arg)?;
+                match value.trim().parse::<u32>() {
+                    Ok(limit) if limit > 0 => config.attempt_ceiling = limit,
+                    _ => return Err(format!("{} must be a positive whole number, got {}", arg, value)),
+                }
+            },
+            "--proximity-every" => {
+                let value = next_value(&mut args, &arg)?;
+                match value.trim().parse::<u32>() {
+                    Ok(interval) if interval > 0 => config.proximity_interval = interval,
+                    _ => return Err(format!("{} must be a positive whole number, got {}", arg, value)),
+                }
+            },
+            "--max-line" => {
+                let value = next_value(&mut args, &arg)?;
+                match value.trim().parse::<usize>() {
+                    Ok(bytes) if bytes > 0 => config.max_line = bytes,
+                    _ => return Err(format!("{} must be a positive whole number, got {}", arg, value)),
+                }
+            },
+            "--mercy" => config.mercy = true,
+            "--oneline" => config.oneline = true,
+            "--guesses-from" => config.guesses_from = Some(PathBuf::from(next_value(&mut args, &arg)?)),
+            "--prompt" => {
+                let template = next_value(&mut args, &arg)?;
+                validate_prompt(&template)?;
+                config.prompt = template;
+            },
+            "--time-limit" => {
+                let seconds = parse_positive(&next_value(&mut args, &arg)?, &arg)?;
+                config.time_limit = Some(Duration::from_secs_f64(seconds));
+            },
+            "--timeout-reveal" => {
+                let value = next_value(&mut args, &arg)?;
+                config.timeout_reveal = TimeoutReveal::from_name(&value)
+                    .ok_or_else(|| format!("Unknown timeout reveal '{}'. Valid: none, quartile, secret", value))?;
+            },
+            "--hint-deck" => config.hint_deck = true,
+            "--stdin-range" => config.stdin_range = true,
+            "--sticky-range" => config.sticky_range = true,
+            "--compact" => config.compact = true,
+            "--explain-hints" => config.explain_hints = true,
+            "--hint-scale" => config.hint_scale = parse_positive(&next_value(&mut args, &arg)?, &arg)?,
+            "--max-hint-value" => config.max_hint_value = parse_positive(&next_value(&mut args, &arg)?, &arg)?,
+            "--quiet" => config.quiet = true,
+            "--interactive" => config.interactive = Some(true),
+            "--no-interactive" => config.interactive = Some(false),
+            "--distribution" => {
+                let value = next_value(&mut args, &arg)?;
+                config.distribution = Distribution::from_name(&value)
+                    .ok_or_else(|| format!("Unknown distribution '{}'. Valid: uniform, center, edges", value))?;
+            },
+            "--lucky" => config.lucky = Some(parse_number(&next_value(&mut args, &arg)?, &arg)?),
+            "--reveal-after" => config.reveal_after = Some(parse_whole(&next_value(&mut args, &arg)?, &arg)?),
+            "--unlock-wins" => config.unlock_wins = parse_whole(&next_value(&mut args, &arg)?, &arg)?,
+            "--hint-limit" => {
+                let (category, limit) = parse_hint_limit(&next_value(&mut args, &arg)?)?;
+                config.hint_limits.insert(category, limit);
+            },
+            "--hint-budget" => config.hint_budget = Some(parse_whole(&next_value(&mut args, &arg)?, &arg)?),
+            "--point-budget" => {
+                let value = next_value(&mut args, &arg)?;
+                match value.trim().parse::<u32>() {
+                    Ok(points) if points > 0 => config.point_budget = Some(points),
+                    _ => return Err(format!("{} must be a positive whole number, got {}", arg, value)),
+                }
+            },
+            "--multiple-of" => config.multiple_of = Some(parse_whole(&next_value(&mut args, &arg)?, &arg)?),
+            "--surprise" => config.surprise = true,
+            "--reset-attempts" => config.carry_attempts = false,
+            "--no-nudge" => config.nudge = false,
+            "--expr-input" => config.expr_input = true,
+            "--secret-decimals" => {
+                let value = next_value(&mut args, &arg)?;
+                match value.trim().parse::<usize>() {
+                    Ok(places) if places <= 10 => config.secret_decimals = Some(places),
+                    _ => return Err(format!("{} must be a whole number from 0 to 10, got {}", arg, value)),
+                }
+            },
+            "--proximity-only" => config.proximity_only = true,
+            "--repeat-guesses" => {
+                let value = next_value(&mut args, &arg)?;
+                config.repeat_guesses = RepeatGuesses::from_name(&value)
+                    .ok_or_else(|| format!("Unknown repeat-guess policy '{}'. Valid: warn, confirm, reject", value))?;
+            },
+            "--set-secret" => config.set_secret = true,
+            "--adaptive" => config.adaptive = true,
+            "--escalating" => config.escalating = true,
+            "--bell" => config.bell = true,
+            "--trace" => config.show_trace = true,
+            "--blind" => config.blind = true,
+            "--exclusive-min" => config.exclusive_min = true,
+            "--exclusive-max" => config.exclusive_max = true,
+            "--hide-retry" => {
+                let name = next_value(&mut args, &arg)?;
+                validate_hidden_item(&name)?;
+                config.hidden_retry.push(name);
+            },
+            "--integer" => {
+                config.mode = GameMode::Integer;
+                config.explicit_mode = true;
+            },
+            "--decimal" => {
+                config.mode = GameMode::Decimal;
+                config.explicit_mode = true;
+            },
+            "--seed" => config.seed = Some(parse_whole(&next_value(&mut args, &arg)?, &arg)?),
+            "--difficulty" => {
+                let value = next_value(&mut args, &arg)?;
+                let difficulty = Difficulty::from_name(&value)
+                    .ok_or_else(|| format!("Unknown difficulty '{}'. Valid: easy, medium, hard, insane", value))?;
+                (config.range_start, config.range_end) = difficulty.range();
+                config.difficulty = difficulty;
+            },
+            "--data-dir" => config.data_dir = PathBuf::from(next_value(&mut args, &arg)?),
+            "--dice" => {
+                let dice = parse_dice(&next_value(&mut args, &arg)?)?;
+                (config.range_start, config.range_end) = (dice.min(), dice.max());
+                config.distribution = Distribution::Dice(dice);
+                config.mode = GameMode::Integer;
+                config.explicit_mode = true;
+            },
+            "--range-preset" => {
+                let (start, end, mode) = range_preset_or_err(&next_value(&mut args, &arg)?)?;
+                config.range_start = start;
+                config.range_end = end;
+                config.mode = mode;
+                config.explicit_mode = true;
+            },
+            _ => return Err(format!("Unknown argument: {}", arg)),
+        }
+    }
+
+    Ok(())
+}
+
+/// Save files go in ~/.guessing_game, or the working directory without a home
+fn default_data_dir() -> PathBuf {
+    match std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")) {
+        Some(home) => PathBuf::from(home).join(".guessing_game"),
+        None => PathBuf::from(".guessing_game"),
+    }
+}
+
+/// Finds the value of `--config` among the arguments, if present
+fn config_file_path(args: &[String]) -> Result<Option<PathBuf>, String> {
+    match args.iter().position(|arg| arg == "--config") {
+        Some(index) => match args.get(index + 1) {
+            Some(path) => Ok(Some(PathBuf::from(path))),
+            None => Err(String::from("Missing value for --config")),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Reads a `key = value` config file and turns it into flags
+/// Each key is a flag name without the leading dashes; `true` enables a
+/// switch, `false` leaves it off, and `#` starts a comment line
+fn read_config_file(path: &Path) -> Result<Vec<String>, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("Could not read config file {}: {}", path.display(), e))?;
+
+    let mut flags = Vec::new();
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("{}:{}: expected key = value", path.display(), number + 1));
+        };
+        let value = value.trim().trim_matches('"');
+        match value {
+            "true" => flags.push(format!("--{}", key.trim())),
+            "false" => {},
+            _ => {
+                flags.push(format!("--{}", key.trim()));
+                flags.push(value.to_string());
+            },
+        }
+    }
+    Ok(flags)
+}
+
+impl GameConfig {
+    /// Whether to show menus and prompts
+    /// Without a flag this follows stdin: piped or redirected input gets
+    /// no hints and no extra prompts, and the session ends after a win
+    pub fn interactive(&self) -> bool {
+        self.interactive.unwrap_or_else(|| io::stdin().is_terminal())
+    }
+
+    /// The guessing range between two bounds, with this config's open/closed ends
+    pub fn game_range(&self, start: f64, end: f64) -> GameRange {
+        GameRange { start, end, start_inclusive: !self.exclusive_min, end_inclusive: !self.exclusive_max }
+    }
+
+    /// Renders the effective settings in the same `key = value` format
+    /// the config file uses, so the output can be loaded back with `--config`
+    /// Only settings that differ from the defaults are active lines; the rest
+    /// (and a mode inferred from the range) are shown commented out. One-shot
+    /// actions like `--print-config` and `--dump-hints` are not settings and
+    /// are left out
+    pub fn to_config_text(&self) -> String {
+        fn optional<T: std::fmt::Display>(key: &str, value: Option<T>) -> String {
+            match value {
+                Some(value) => format!("{} = {}\n", key, value),
+                None => format!("# {} = (unset)\n", key),
+            }
+        }
+        fn setting<T: std::fmt::Display, D: std::fmt::Display>(key: &str, value: T, default: D) -> String {
+            let value = value.to_string();
+            if value == default.to_string() {
+                format!("# {} = {}\n", key, value)
+            } else {
+                format!("{} = {}\n", key, value)
+            }
+        }
+        fn switch(key: &str, on: bool) -> String {
+            setting(key, on, false)
+        }
+        let quoted = |value: &dyn std::fmt::Display| format!("\"{}\"", value);
+
+        let default = GameConfig::default();
+        let mut text = String::new();
+        text.push_str(&setting("player", quoted(&self.player), quoted(&default.player)));
+        // Difficulty and dice reset the range, so they go before it
+        text.push_str(&setting("difficulty", self.difficulty.name(), default.difficulty.name()));
+        match self.distribution {
+            Distribution::Dice(dice) => text.push_str(&format!("dice = {}\n", dice)),
+            distribution => text.push_str(&setting("distribution", distribution.name(), default.distribution.name())),
+        }
+        // The range is always written, since difficulty or dice may have moved it
+        text.push_str(&format!("min = {}\n", self.range_start));
+        text.push_str(&format!("max = {}\n", self.range_end));
+        let mode = if self.mode == GameMode::Integer { "integer" } else { "decimal" };
+        if self.explicit_mode {
+            text.push_str(&format!("{} = true\n", mode));
+        } else {
+            text.push_str(&format!("# {} = true (inferred from the range)\n", mode));
+        }
+        text.push_str(&switch("exclusive-min", self.exclusive_min));
+        text.push_str(&switch("exclusive-max", self.exclusive_max));
+        text.push_str(&setting("max-span", self.max_span, default.max_span));
+        text.push_str(&setting("tolerance", self.tolerance, default.tolerance));
+        text.push_str(&setting("data-dir", quoted(&self.data_dir.display()), quoted(&default.data_dir.display())));
+        text.push_str(&setting("prompt", quoted(&self.prompt), quoted(&default.prompt)));
+        text.push_str(&optional("seed", self.seed));
+        text.push_str(&optional("target", self.target));
+        text.push_str(&setting("attempt-ceiling", self.attempt_ceiling, default.attempt_ceiling));
+        text.push_str(&optional("alert-distance", self.alert_distance));
+        text.push_str(&optional("hint-budget", self.hint_budget));
+        text.push_str(&optional("point-budget", self.point_budget));
+        text.push_str(&switch("hint-deck", self.hint_deck));
+        text.push_str(&switch("stdin-range", self.stdin_range));
+        text.push_str(&switch("sticky-range", self.sticky_range));
+        text.push_str(&switch("compact", self.compact));
+        text.push_str(&switch("explain-hints", self.explain_hints));
+        text.push_str(&setting("hint-scale", self.hint_scale, default.hint_scale));
+        text.push_str(&setting("max-hint-value", self.max_hint_value, default.max_hint_value));
+        text.push_str(&switch("quiet", self.quiet));
+        text.push_str(&switch("interactive", self.interactive == Some(true)));
+        text.push_str(&switch("no-interactive", self.interactive == Some(false)));
+        text.push_str(&setting("proximity-every", self.proximity_interval, default.proximity_interval));
+        text.push_str(&switch("no-nudge", !self.nudge));
+        text.push_str(&switch("demo", self.demo));
+        text.push_str(&switch("animate", self.animate));
+        text.push_str(&switch("narrate", self.narrate));
+        text.push_str(&setting("demo-delay", self.demo_delay.as_millis(), default.demo_delay.as_millis()));
+        text.push_str(&switch("celebrate", self.celebrate));
+        text.push_str(&switch("certificate", self.certificate));
+        text.push_str(&optional("secret-decimals", self.secret_decimals));
+        text.push_str(&switch("expr-input", self.expr_input));
+        text.push_str(&setting("repeat-guesses", self.repeat_guesses.name(), default.repeat_guesses.name()));
+        text.push_str(&switch("proximity-only", self.proximity_only));
+        for band in &self.bands {
+            text.push_str(&format!("band = {}..{}\n", band.start, band.end));
+        }
+        for category in HintCategory::ALL {
+            if let Some(limit) = self.hint_limits.get(&category) {
+                text.push_str(&format!("hint-limit = {}={}\n", category.name(), limit));
+            }
+        }
+        text.push_str(&optional("multiple-of", self.multiple_of));
+        text.push_str(&optional("reveal-after", self.reveal_after));
+        text.push_str(&setting("unlock-wins", self.unlock_wins, default.unlock_wins));
+        text.push_str(&optional("lucky", self.lucky));
+        text.push_str(&optional("time-limit", self.time_limit.map(|limit| limit.as_secs_f64())));
+        text.push_str(&setting("timeout-reveal", self.timeout_reveal.name(), default.timeout_reveal.name()));
+        text.push_str(&optional("idle-timeout", self.idle_timeout.map(|limit| limit.as_secs_f64())));
+        text.push_str(&setting("idle-grace", self.idle_grace.as_secs_f64(), default.idle_grace.as_secs_f64()));
+        text.push_str(&setting("max-line", self.max_line, default.max_line));
+        text.push_str(&optional("export-md", self.export_md.as_ref().map(|path| path.display())));
+        text.push_str(&optional("export-heatmap", self.export_heatmap.as_ref().map(|path| path.display())));
+        text.push_str(&optional("guesses-from", self.guesses_from.as_ref().map(|path| path.display())));
+        text.push_str(&switch("mercy", self.mercy));
+        text.push_str(&switch("oneline", self.oneline));
+        text.push_str(&switch("surprise", self.surprise));
+        text.push_str(&switch("reset-attempts", !self.carry_attempts));
+        text.push_str(&switch("set-secret", self.set_secret));
+        text.push_str(&switch("adaptive", self.adaptive));
+        text.push_str(&switch("escalating", self.escalating));
+        text.push_str(&switch("bell", self.bell));
+        text.push_str(&switch("trace", self.show_trace));
+        text.push_str(&switch("blind", self.blind));
+        for name in &self.hidden_retry {
+            text.push_str(&format!("hide-retry = {}\n", name));
+        }
+        text
+    }
+}
+
+/// Looks up a named range preset
+/// Parameters:
+///   name: &str - preset name such as "dice"
+/// Returns:
+///   (start, end, mode) or None for an unknown name
+pub fn range_preset(name: &str) -> Option<(f64, f64, GameMode)> {
+    RANGE_PRESETS
+        .iter()
+        .find(|(preset, ..)| preset.eq_ignore_ascii_case(name.trim()))
+        .map(|&(_, start, end, mode)| (start, end, mode))
+}
+
+/// Looks up a preset, listing the valid names when it is unknown
+fn range_preset_or_err(name: &str) -> Result<(f64, f64, GameMode), String> {
+    range_preset(name).ok_or_else(|| {
+        let names: Vec<&str> = RANGE_PRESETS.iter().map(|(preset, ..)| *preset).collect();
+        format!("Unknown range preset '{}'. Valid presets: {}", name, names.join(", "))
+    })
+}
+
+/// Picks a mode from the range bounds when none was chosen
+/// Whole-number bounds play as integers; any fractional bound means decimals
+pub fn infer_mode(start: f64, end: f64) -> GameMode {
+    if start.fract() == 0.0 && end.fract() == 0.0 {
+        GameMode::Integer
+    } else {
+        GameMode::Decimal
+    }
+}
+
+/// A guessing range whose ends may each be open or closed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GameRange {
+    pub start: f64,
+    pub end: f64,
+    pub start_inclusive: bool,
+    pub end_inclusive: bool,
+}
+
+impl GameRange {
+    /// Whether a value lies inside the range, honoring open ends
+    pub fn contains(&self, value: f64) -> bool {
+        let above = if self.start_inclusive { value >= self.start } else { value > self.start };
+        let below = if self.end_inclusive { value <= self.end } else { value < self.end };
+        above && below
+    }
+
+    /// Smallest and largest whole numbers inside the range
+    /// Parameters:
+    ///   step: f64 - only multiples of this count (1.0 for any whole number)
+    /// Returns:
+    ///   (low, high) as multiples of `step`, or None if no multiple fits
+    pub fn whole_bounds(&self, step: f64) -> Option<(i64, i64)> {
+        let mut low = (self.start / step).ceil() as i64;
+        if !self.start_inclusive && low as f64 * step <= self.start {
+            low += 1;
+        }
+        let mut high = (self.end / step).floor() as i64;
+        if !self.end_inclusive && high as f64 * step >= self.end {
+            high -= 1;
+        }
+        (low <= high).then_some((low, high))
+    }
+}
+
+impl fmt::Display for GameRange {
+    /// Closed ranges read "1-100"; anything open uses interval notation, e.g. "(0, 100]"
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.start_inclusive && self.end_inclusive {
+            return write!(f, "{}-{}", self.start, self.end);
+        }
+        let open = if self.start_inclusive { '[' } else { '(' };
+        let close = if self.end_inclusive { ']' } else { ')' };
+        write!(f, "{}{}, {}{}", open, self.start, self.end, close)
+    }
+}
+
+/// Checks that a guessing range is usable
+/// Parameters:
+///   start: f64 - lower bound
+///   end: f64 - upper bound
+///   max_span: f64 - widest allowed distance between the bounds
+/// Returns:
+///   Ok, or a message explaining why the range was rejected
+pub fn validate_range(start: f64, end: f64, max_span: f64) -> Result<(), String> {
+    if !start.is_finite() || !end.is_finite() {
+        return Err(String::from("Range bounds must be finite numbers."));
+    }
+    if start >= end {
+        return Err(format!("Range start ({}) must be less than range end ({}).", start, end));
+    }
+    if end - start > max_span {
+        return Err(format!("Range span {} exceeds the maximum of {}.", end - start, max_span));
+    }
+    Ok(())
+}
+
+/// Checks that a win tolerance leaves something to guess
+/// A tolerance covering half the range or more would make nearly every
+/// guess a win, so it is rejected
+/// Parameters:
+///   tolerance: f64 - allowed distance from the secret
+///   start: f64 - lower bound
+///   end: f64 - upper bound
+/// Returns:
+///   Ok, or a message explaining why the tolerance was rejected
+pub fn validate_tolerance(tolerance: f64, start: f64, end: f64) -> Result<(), String> {
+    if tolerance < 0.0 {
+        return Err(format!("Tolerance ({}) cannot be negative.", tolerance));
+    }
+    if tolerance * 2.0 >= end - start {
+        return Err(format!(
+            "Tolerance {} is too large for the range {}-{}; it must be less than {}.",
+            tolerance,
+            start,
+            end,
+            (end - start) / 2.0
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that a prompt template only uses the `{range}` placeholder
+/// Parameters:
+///   template: &str - prompt text to check
+/// Returns:
+///   Ok, or a message naming the unsupported placeholder
+pub fn validate_prompt(template: &str) -> Result<(), String> {
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        let after = &rest[open..];
+        match after.find('}') {
+            Some(close) if &after[..=close] == "{range}" => rest = &after[close + 1..],
+            Some(close) => return Err(format!("Unsupported placeholder {} in prompt; only {{range}} is allowed", &after[..=close])),
+            None => return Err(String::from("Unclosed '{' in prompt template")),
+        }
+    }
+    if rest.contains('}') {
+        return Err(String::from("Unmatched '}' in prompt template"));
+    }
+    Ok(())
+}
+
+/// Pulls the value that must follow a flag
+/// Parameters:
+///   args: remaining arguments
+///   flag: flag name, used in the error message
+fn next_value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    args.next().ok_or_else(|| format!("Missing value for {}", flag))
+}
+
+/// Parses a numeric flag value that must be greater than zero
+fn parse_positive(value: &str, flag: &str) -> Result<f64, String> {
+    let number = parse_number(value, flag)?;
+    if number <= 0.0 {
+        return Err(format!("{} must be greater than 0", flag));
+    }
+    Ok(number)
+}
+
+/// Parses a `START..END` range value
+fn parse_span(value: &str, flag: &str) -> Result<(f64, f64), String> {
+    let Some((start, end)) = value.split_once("..") else {
+        return Err(format!("{} expects START..END, got {}", flag, value));
+    };
+    Ok((parse_number(start, flag)?, parse_number(end, flag)?))
+}
+
+/// Derives a range from example values such as `12,45,88`
+/// Returns:
+///   The smallest and largest example, or why the list can't make a range
+pub fn parse_examples(value: &str, flag: &str) -> Result<(f64, f64), String> {
+    let examples = value.split(',').map(|example| parse_number(example, flag)).collect::<Result<Vec<f64>, String>>()?;
+    let low = examples.iter().copied().fold(f64::INFINITY, f64::min);
+    let high = examples.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if examples.is_empty() || low == high {
+        return Err(format!("{} needs at least two different values, got {}", flag, value));
+    }
+    Ok((low, high))
+}
+
+/// Parses a `CATEGORY=N` hint allowance such as `easy=2`
+fn parse_hint_limit(value: &str) -> Result<(HintCategory, u32), String> {
+    let Some((name, limit)) = value.split_once('=') else {
+        return Err(format!("--hint-limit expects CATEGORY=N, got {}", value));
+    };
+    let category = HintCategory::from_name(name).ok_or_else(|| {
+        let names: Vec<&str> = HintCategory::ALL.iter().map(HintCategory::name).collect();
+        format!("Unknown hint category '{}' (expected one of: {})", name.trim(), names.join(", "))
+    })?;
+    Ok((category, parse_whole(limit, "--hint-limit")?))
+}
+
+/// Parses a whole-number flag value
+fn parse_whole<T: std::str::FromStr>(value: &str, flag: &str) -> Result<T, String> {
+    value.trim().parse().map_err(|_| format!("{} must be a whole number, got {}", flag, value))
+}
+
+/// Parses a numeric flag value
+/// Parameters:
+///   value: raw text following the flag
+///   flag: flag name, used in the error message
+fn parse_number(value: &str, flag: &str) -> Result<f64, String> {
+    match value.trim().parse::<f64>() {
+        Ok(n) if n.is_finite() => Ok(n),
+        _ => Err(format!("Invalid number for {}: {}", flag, value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    /// Writes a config file into a fresh temp dir and returns its path
+    fn config_file(name: &str, contents: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("guessing-game-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("game.conf");
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn printed_config_shows_the_command_line_winning() {
+        let path = config_file("override", "hint-budget = 2\nplayer = \"File\"\n");
+        let config = resolve_config(args(&format!("--config {} --hint-budget 5 --print-config", path.display()))).unwrap();
+        let text = config.to_config_text();
+        assert!(text.contains("hint-budget = 5\n"));
+        assert!(text.contains("player = \"File\"\n"));
+        assert!(!text.contains("print-config"));
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn printed_config_loads_back_unchanged() {
+        for line in ["--range 1..10.5 --no-nudge --seed 9", "--difficulty hard --range 1..50 --integer --hint-limit easy=2", "--dice 2d6 --trace"] {
+            let config = resolve_config(args(line)).unwrap();
+            let path = config_file("round-trip", &config.to_config_text());
+            let reloaded = resolve_config(args(&format!("--config {}", path.display()))).unwrap();
+            assert_eq!(reloaded.to_config_text(), config.to_config_text(), "{}", line);
+            assert_eq!(reloaded.explicit_mode, config.explicit_mode, "{}", line);
+            assert_eq!((reloaded.range_start, reloaded.range_end), (config.range_start, config.range_end), "{}", line);
+            fs::remove_dir_all(path.parent().unwrap()).unwrap();
+        }
+    }
+
+    #[test]
+    fn inferred_mode_is_not_written_as_a_setting() {
+        let text = resolve_config(args("--range 1..10.5")).unwrap().to_config_text();
+        assert!(text.contains("# decimal = true (inferred from the range)\n"));
+        assert!(!text.lines().any(|line| line == "integer = false" || line == "decimal = true"));
+        assert!(text.contains("# no-nudge = false\n"));
+    }
+
+    #[test]
+    fn oversized_range_is_rejected() {
+        assert!(validate_range(0.0, 2e9, DEFAULT_MAX_SPAN).is_err());
+        assert!(resolve_config(args("--min 0 --max 2000000000")).is_err_and(|e| e.contains("exceeds the maximum")));
+    }
+
+    #[test]
+    fn non_finite_or_equal_bounds_are_rejected() {
+        assert!(validate_range(f64::NAN, 10.0, DEFAULT_MAX_SPAN).is_err());
+        assert!(validate_range(1.0, f64::INFINITY, DEFAULT_MAX_SPAN).is_err());
+        assert!(validate_range(5.0, 5.0, DEFAULT_MAX_SPAN).is_err());
+        assert!(resolve_config(args("--min NaN --max 10")).is_err());
+    }
+
+    #[test]
+    fn span_at_the_maximum_is_accepted() {
+        assert_eq!(validate_range(0.0, DEFAULT_MAX_SPAN, DEFAULT_MAX_SPAN), Ok(()));
+        let config = resolve_config(args("--min 0 --max 1000000000")).unwrap();
+        assert_eq!((config.range_start, config.range_end), (0.0, 1e9));
+    }
+
+    #[test]
+    fn exclusive_bounds_reject_the_bound_itself() {
+        let config = resolve_config(args("--range 0..100 --exclusive-min --exclusive-max")).unwrap();
+        let range = config.game_range(0.0, 100.0);
+        assert!(!range.contains(0.0) && !range.contains(100.0));
+        assert!(range.contains(0.5) && range.contains(99.5));
+        let closed = GameConfig::default().game_range(0.0, 100.0);
+        assert!(closed.contains(0.0) && closed.contains(100.0));
+        assert_eq!(range.whole_bounds(1.0), Some((1, 99)));
+    }
+
+    #[test]
+    fn exclusive_bounds_are_never_generated() {
+        use crate::utils::{gen_rand, seeded_rng};
+        let range = GameRange { start: 0.0, end: 1.0, start_inclusive: false, end_inclusive: false };
+        let mut rng = seeded_rng(8);
+        for _ in 0..500 {
+            // Rounding to whole numbers would land on a bound every time
+            let value = gen_rand(&mut rng, range, Distribution::Uniform, Some(0));
+            assert!(value > 0.0 && value < 1.0, "{}", value);
+        }
+    }
+
+    #[test]
+    fn range_comes_from_the_examples() {
+        assert_eq!(parse_examples("12,45,88", "--range-from"), Ok((12.0, 88.0)));
+        assert_eq!(parse_examples("88, 12", "--range-from"), Ok((12.0, 88.0)));
+        let config = resolve_config(args("--range-from 12,45,88")).unwrap();
+        assert_eq!((config.range_start, config.range_end), (12.0, 88.0));
+    }
+
+    #[test]
+    fn examples_need_two_different_values() {
+        assert!(parse_examples("12", "--range-from").is_err());
+        assert!(parse_examples("7,7,7", "--range-from").is_err());
+        assert!(parse_examples("12,abc", "--range-from").is_err());
+    }
+
+    #[test]
+    fn mirror_builds_a_signed_range() {
+        let config = resolve_config(args("--mirror 50")).unwrap();
+        assert_eq!((config.range_start, config.range_end), (-50.0, 50.0));
+        assert_eq!(config.mode, GameMode::Integer);
+        let range = config.game_range(config.range_start, config.range_end);
+        assert!(range.contains(-50.0) && range.contains(-17.0) && range.contains(50.0));
+        assert!(!range.contains(-51.0));
+        assert!(resolve_config(args("--mirror 0")).is_err());
+    }
+
+    #[test]
+    fn timeout_reveal_defaults_to_the_secret() {
+        assert_eq!(resolve_config(args("")).unwrap().timeout_reveal, TimeoutReveal::Secret);
+        assert_eq!(resolve_config(args("--timeout-reveal quartile")).unwrap().timeout_reveal, TimeoutReveal::Quartile);
+        assert!(resolve_config(args("--timeout-reveal everything")).is_err());
+    }
+
+    #[test]
+    fn dice_notation_sets_the_bounds() {
+        let dice = parse_dice("3d6").unwrap();
+        assert_eq!((dice.min(), dice.max()), (3.0, 18.0));
+        assert_eq!(parse_dice("D20").unwrap(), DiceRange { count: 1, sides: 20 });
+
+        let config = resolve_config(args("--dice 3d6")).unwrap();
+        assert_eq!((config.range_start, config.range_end), (3.0, 18.0));
+        assert!(matches!(config.distribution, Distribution::Dice(DiceRange { count: 3, sides: 6 })));
+    }
+
+    #[test]
+    fn malformed_dice_are_rejected() {
+        for spec in ["3x6", "3d", "d1", "0d6", "3d6.5", "many"] {
+            assert!(parse_dice(spec).is_err_and(|e| e.contains(spec)), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn range_presets_have_their_bounds() {
+        assert!(matches!(range_preset("dice"), Some((1.0, 6.0, GameMode::Integer))));
+        assert!(matches!(range_preset("percent"), Some((0.0, 100.0, GameMode::Integer))));
+        assert!(matches!(range_preset(" Clock "), Some((1.0, 12.0, GameMode::Integer))));
+
+        let config = resolve_config(args("--range-preset clock")).unwrap();
+        assert_eq!((config.range_start, config.range_end), (1.0, 12.0));
+    }
+
+    #[test]
+    fn unknown_range_preset_lists_the_valid_ones() {
+        assert!(range_preset("weekday").is_none());
+        assert!(resolve_config(args("--range-preset weekday"))
+            .is_err_and(|e| e == "Unknown range preset 'weekday'. Valid presets: dice, percent, clock"));
+    }
+
+    #[test]
+    fn prompt_templates_only_accept_the_range_placeholder() {
+        assert!(validate_prompt(DEFAULT_PROMPT).is_ok());
+        assert!(validate_prompt("Your number? {range} > ").is_ok());
+        assert!(validate_prompt("No placeholder at all: ").is_ok());
+        assert!(validate_prompt("Guess {min}-{max}: ").is_err_and(|e| e.contains("{min}")));
+        assert!(validate_prompt("Guess {range: ").is_err_and(|e| e.contains("Unclosed")));
+        assert!(validate_prompt("Guess range}: ").is_err_and(|e| e.contains("Unmatched")));
+
+        let flags = ["--prompt", "Pick {range} > "].map(String::from);
+        let config = resolve_config(flags).unwrap();
+        assert_eq!(config.prompt, "Pick {range} > ");
+        assert!(resolve_config(["--prompt", "Pick {low} > "].map(String::from)).is_err());
+    }
+
+    #[test]
+    fn tolerance_covering_half_the_range_is_rejected() {
+        assert!(validate_tolerance(0.5, 1.0, 2.0).is_err_and(|e| e.contains("must be less than 0.5")));
+        assert!(validate_tolerance(-0.1, 1.0, 100.0).is_err_and(|e| e.contains("negative")));
+        assert!(resolve_config(args("--decimal --min 1 --max 2 --tolerance 0.6")).is_err_and(|e| e.contains("too large")));
+    }
+
+    #[test]
+    fn sensible_tolerance_passes_untouched() {
+        assert!(validate_tolerance(0.49, 1.0, 2.0).is_ok());
+        let config = resolve_config(args("--decimal --min 1 --max 2 --tolerance 0.1")).unwrap();
+        assert_eq!(config.tolerance, 0.1);
+    }
+
+    #[test]
+    fn whole_bounds_infer_integer_mode() {
+        assert_eq!(infer_mode(1.0, 10.0), GameMode::Integer);
+        assert_eq!(infer_mode(-5.0, 0.0), GameMode::Integer);
+        assert_eq!(resolve_config(args("--min 1 --max 10")).unwrap().mode, GameMode::Integer);
+    }
+
+    #[test]
+    fn fractional_bounds_infer_decimal_mode() {
+        assert_eq!(infer_mode(0.5, 10.0), GameMode::Decimal);
+        assert_eq!(infer_mode(1.0, 9.75), GameMode::Decimal);
+        assert_eq!(resolve_config(args("--min 1 --max 9.5")).unwrap().mode, GameMode::Decimal);
+    }
+
+    #[test]
+    fn explicit_mode_beats_the_inferred_one() {
+        let config = resolve_config(args("--decimal --min 1 --max 10")).unwrap();
+        assert_eq!(config.mode, GameMode::Decimal);
+    }
+
+    #[test]
+    fn mirrored_range_accepts_negative_guesses_in_play() {
+        let config = resolve_config(args("--mirror 50")).unwrap();
+        let range = (config.range_start, config.range_end);
+        let mut round = crate::round::RoundState::new(None, config.attempt_ceiling);
+        crate::input::use_script(["-40", "-17"].map(String::from).to_vec());
+        assert!(!crate::utils::game_loop(-17.0, &mut round, range, &config).unwrap());
+        assert!(crate::utils::game_loop(-17.0, &mut round, range, &config).unwrap());
+        assert_eq!(round.guesses(), [-40.0, -17.0]);
+    }
+}
@@ -0,0 +1,127 @@
+//! Game error module
+//!
+//! One error type for everything that can stop a session:
+//! - Input ending (closed stdin or an idle player)
+//! - Terminal I/O failures
+//! - Comparisons against non-finite numbers
+//! - Rounds that run past the attempt ceiling
+//! - Bad command-line or config-file settings
+
+use std::fmt;
+use std::io;
+use crate::input::InputEnd;
+
+/// Why the game could not carry on
+#[derive(Debug)]
+pub enum GameError {
+    /// No more input can be read
+    Input(InputEnd),
+    /// Reading or writing the terminal failed
+    Io(io::Error),
+    /// A guess or secret was NaN, so it cannot be compared
+    NonFiniteComparison { guess: f64, secret: f64 },
+    /// A setting was missing or invalid
+    ParseConfig(String),
+    /// A round used up the configured maximum number of attempts
+    AttemptCeiling(u32),
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::Input(InputEnd::Eof) => write!(f, "Input closed."),
+            GameError::Input(InputEnd::Idle) => write!(f, "No input received."),
+            GameError::Io(e) => write!(f, "Terminal error: {}", e),
+            GameError::NonFiniteComparison { guess, secret } => {
+                write!(f, "Cannot compare guess {} with secret {}.", guess, secret)
+            },
+            GameError::ParseConfig(message) => write!(f, "{}", message),
+            GameError::AttemptCeiling(limit) => write!(f, "Gave up after {} attempts on one round.", limit),
+        }
+    }
+}
+
+impl GameError {
+    /// Process exit code for a session that stops on this error
+    /// Running out of input is a normal way to stop; anything else is a failure
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            GameError::Input(_) => 0,
+            _ => 1,
+        }
+    }
+}
+
+impl From<InputEnd> for GameError {
+    fn from(end: InputEnd) -> Self {
+        GameError::Input(end)
+    }
+}
+
+impl From<io::Error> for GameError {
+    fn from(e: io::Error) -> Self {
+        GameError::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{GameConfig, parse_args};
+    use crate::input;
+    use crate::round::RoundState;
+    use crate::utils::game_loop;
+
+    /// Plays scripted guesses against a secret until the loop stops
+    fn play(secret: f64, guesses: &[&str], config: &GameConfig) -> Result<bool, GameError> {
+        input::use_script(guesses.iter().map(|guess| guess.to_string()).collect());
+        let mut round = RoundState::new(None, config.attempt_ceiling);
+        loop {
+            if game_loop(secret, &mut round, (1.0, 100.0), config)? {
+                return Ok(true);
+            }
+        }
+    }
+
+    #[test]
+    fn closed_input_ends_the_session_cleanly() {
+        let error = play(50.0, &["10"], &GameConfig::default()).unwrap_err();
+        assert!(matches!(error, GameError::Input(InputEnd::Eof)));
+        assert_eq!(error.to_string(), "Input closed.");
+        assert_eq!(GameError::from(InputEnd::Idle).to_string(), "No input received.");
+        assert_eq!(error.exit_code(), 0);
+    }
+
+    #[test]
+    fn io_failure_is_reported() {
+        let error = GameError::from(io::Error::new(io::ErrorKind::BrokenPipe, "pipe closed"));
+        assert!(matches!(error, GameError::Io(_)));
+        assert_eq!(error.to_string(), "Terminal error: pipe closed");
+        assert_eq!(error.exit_code(), 1);
+    }
+
+    #[test]
+    fn nan_secret_is_an_error_not_a_panic() {
+        let error = play(f64::NAN, &["50"], &GameConfig::default()).unwrap_err();
+        assert!(matches!(error, GameError::NonFiniteComparison { guess: 50.0, .. }));
+        assert_eq!(error.to_string(), "Cannot compare guess 50 with secret NaN.");
+        assert_eq!(error.exit_code(), 1);
+    }
+
+    #[test]
+    fn bad_setting_is_a_config_error() {
+        let error = parse_args([String::from("--bogus")]).err().unwrap();
+        assert!(matches!(error, GameError::ParseConfig(_)));
+        assert_eq!(error.to_string(), "Unknown argument: --bogus");
+        assert_eq!(error.exit_code(), 1);
+    }
+
+    #[test]
+    fn attempt_ceiling_stops_a_runaway_round() {
+        let config = GameConfig { attempt_ceiling: 2, ..GameConfig::default() };
+        let error = play(50.0, &["10", "20", "30"], &config).unwrap_err();
+        assert!(matches!(error, GameError::AttemptCeiling(2)));
+        assert_eq!(error.to_string(), "Gave up after 2 attempts on one round.");
+        assert_eq!(error.exit_code(), 1);
+    }
+}
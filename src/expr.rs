@@ -0,0 +1,120 @@
+//! Arithmetic guesses module
+//!
+//! Lets guesses like "1/3" or "50+5" be typed when `--expr-input` is on:
+//! - Numbers with + - * / (also − × ÷) and parentheses
+//! - Usual precedence, left to right, with unary minus
+//! - Malformed expressions come back as a message saying what is wrong
+
+/// Evaluates a small arithmetic expression
+/// Parameters:
+///   text: &str - expression such as "3*(7+1)"
+/// Returns:
+///   The value, or a message describing the problem
+pub fn evaluate(text: &str) -> Result<f64, String> {
+    let chars: Vec<char> = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '−' => '-',
+            '×' => '*',
+            '÷' => '/',
+            c => c,
+        })
+        .collect();
+    if chars.is_empty() {
+        return Err(String::from("empty expression"));
+    }
+    let mut parser = Parser { chars, pos: 0 };
+    let value = parser.sum()?;
+    if let Some(c) = parser.peek() {
+        return Err(format!("unexpected '{}'", c));
+    }
+    if !value.is_finite() {
+        return Err(String::from("result is not a finite number (division by zero?)"));
+    }
+    Ok(value)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    /// Terms joined by + and -
+    fn sum(&mut self) -> Result<f64, String> {
+        let mut value = self.product()?;
+        while let Some(op @ ('+' | '-')) = self.peek() {
+            self.pos += 1;
+            let rhs = self.product()?;
+            value = if op == '+' { value + rhs } else { value - rhs };
+        }
+        Ok(value)
+    }
+
+    /// Factors joined by * and /
+    fn product(&mut self) -> Result<f64, String> {
+        let mut value = self.factor()?;
+        while let Some(op @ ('*' | '/')) = self.peek() {
+            self.pos += 1;
+            let rhs = self.factor()?;
+            value = if op == '*' { value * rhs } else { value / rhs };
+        }
+        Ok(value)
+    }
+
+    /// A number, a bracketed expression or a negated factor
+    fn factor(&mut self) -> Result<f64, String> {
+        match self.peek() {
+            Some('-') => {
+                self.pos += 1;
+                Ok(-self.factor()?)
+            },
+            Some('(') => {
+                self.pos += 1;
+                let value = self.sum()?;
+                if self.peek() != Some(')') {
+                    return Err(String::from("missing ')'"));
+                }
+                self.pos += 1;
+                Ok(value)
+            },
+            Some(c) if c.is_ascii_digit() || c == '.' => self.number(),
+            Some(c) => Err(format!("unexpected '{}'", c)),
+            None => Err(String::from("expression ends too early")),
+        }
+    }
+
+    fn number(&mut self) -> Result<f64, String> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit() || c == '.') {
+            self.pos += 1;
+        }
+        let literal: String = self.chars[start..self.pos].iter().collect();
+        literal.parse().map_err(|_| format!("bad number '{}'", literal))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simple_expressions_evaluate() {
+        assert_eq!(evaluate("1/4"), Ok(0.25));
+        assert_eq!(evaluate("50+5"), Ok(55.0));
+        assert_eq!(evaluate("3*(7+1)"), Ok(24.0));
+        assert_eq!(evaluate("-2 × 3 − 1"), Ok(-7.0));
+    }
+
+    #[test]
+    fn malformed_expressions_are_rejected() {
+        for text in ["50+", "", "(1+2", "4)", "2**3", "abc"] {
+            assert!(evaluate(text).is_err(), "{}", text);
+        }
+    }
+}
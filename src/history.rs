@@ -0,0 +1,230 @@
+//! Game history module
+//!
+//! Logs every finished game so past results can be browsed:
+//! - One record per game: when, difficulty, outcome and attempts
+//! - Saved as JSON in the data directory (most recent games kept)
+//! - Filtering by outcome, difficulty and date for the `history` command
+//! - Calendar date helpers (UTC, YYYY-MM-DD)
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::config::Difficulty;
+use crate::json::{self, Json};
+
+/// Current history file layout version
+const HISTORY_VERSION: u64 = 1;
+/// History file name inside the data directory
+pub const HISTORY_FILE: &str = "history.json";
+/// Oldest games are dropped beyond this many
+const MAX_GAMES: usize = 1000;
+/// Seconds in a day
+const DAY: u64 = 86_400;
+
+/// One finished game
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameRecord {
+    /// When the game ended, in seconds since the Unix epoch
+    pub timestamp: u64,
+    pub difficulty: Difficulty,
+    pub won: bool,
+    pub attempts: u32,
+}
+
+impl GameRecord {
+    /// A record for a game that just ended
+    pub fn now(difficulty: Difficulty, won: bool, attempts: u32) -> GameRecord {
+        GameRecord { timestamp: unix_now(), difficulty, won, attempts }
+    }
+
+    fn to_json(&self) -> Json {
+        Json::Object(vec![
+            (String::from("timestamp"), Json::Number(self.timestamp as f64)),
+            (String::from("difficulty"), Json::String(self.difficulty.name().to_string())),
+            (String::from("won"), Json::Bool(self.won)),
+            (String::from("attempts"), Json::Number(self.attempts as f64)),
+        ])
+    }
+
+    fn from_json(value: &Json) -> Result<GameRecord, String> {
+        let difficulty = value.get("difficulty").and_then(Json::as_str).ok_or("game has no difficulty")?;
+        Ok(GameRecord {
+            timestamp: value.get("timestamp").and_then(Json::as_u64).ok_or("game has no timestamp")?,
+            difficulty: Difficulty::from_name(difficulty).ok_or(format!("unknown difficulty '{}'", difficulty))?,
+            won: matches!(value.get("won"), Some(Json::Bool(true))),
+            attempts: value.get("attempts").and_then(Json::as_u64).ok_or("game has no attempt count")? as u32,
+        })
+    }
+}
+
+/// Which games the `history` command shows
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct HistoryFilter {
+    /// Only games that were won
+    pub won_only: bool,
+    pub difficulty: Option<Difficulty>,
+    /// Only games on or after this time (seconds since the Unix epoch)
+    pub since: Option<u64>,
+    /// Show at most this many of the most recent matches
+    pub limit: Option<usize>,
+}
+
+impl HistoryFilter {
+    /// Picks the matching games, most recent first
+    pub fn apply<'a>(&self, games: &'a [GameRecord]) -> Vec<&'a GameRecord> {
+        games
+            .iter()
+            .rev()
+            .filter(|game| !self.won_only || game.won)
+            .filter(|game| self.difficulty.is_none_or(|difficulty| game.difficulty == difficulty))
+            .filter(|game| self.since.is_none_or(|since| game.timestamp >= since))
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+}
+
+/// Renders games as a plain-text table
+pub fn history_table(games: &[&GameRecord]) -> String {
+    let mut table = format!("{:<12}{:<12}{:<8}{}\n", "Date", "Difficulty", "Result", "Attempts");
+    for game in games {
+        table.push_str(&format!(
+            "{:<12}{:<12}{:<8}{}\n",
+            calendar_date(game.timestamp),
+            game.difficulty.to_string(),
+            if game.won { "won" } else { "lost" },
+            game.attempts
+        ));
+    }
+    table
+}
+
+/// Path of the history file inside a data directory
+pub fn history_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(HISTORY_FILE)
+}
+
+/// Loads the game log; a missing file means no games yet
+pub fn load_history(path: &Path) -> Result<Vec<GameRecord>, String> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.to_string()),
+    };
+    let value = json::parse(&text)?;
+    match value.get("version").and_then(Json::as_u64) {
+        Some(HISTORY_VERSION) => {},
+        Some(version) => return Err(format!("unsupported history version {}", version)),
+        None => return Err(String::from("not a history file (no version)")),
+    }
+    let games = value.get("games").and_then(Json::as_array).ok_or("history has no game list")?;
+    games.iter().map(GameRecord::from_json).collect()
+}
+
+/// Adds a finished game to the log, creating the data directory if needed
+pub fn append_game(path: &Path, game: GameRecord) -> Result<(), String> {
+    let mut games = load_history(path)?;
+    games.push(game);
+    if games.len() > MAX_GAMES {
+        games.drain(..games.len() - MAX_GAMES);
+    }
+    let value = Json::Object(vec![
+        (String::from("version"), Json::Number(HISTORY_VERSION as f64)),
+        (String::from("games"), Json::Array(games.iter().map(GameRecord::to_json).collect())),
+    ]);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    json::save(path, &value).map_err(|e| e.to_string())
+}
+
+/// Seconds since the Unix epoch
+pub fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs())
+}
+
+/// Formats seconds since the Unix epoch as a UTC date (YYYY-MM-DD)
+pub fn calendar_date(secs: u64) -> String {
+    // Days-to-civil conversion over 400-year eras, counted from 0000-03-01
+    let days = (secs / DAY) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Parses a UTC date (YYYY-MM-DD) into seconds since the Unix epoch
+/// Returns:
+///   Midnight at the start of that day, or a message saying what is wrong
+pub fn parse_date(text: &str) -> Result<u64, String> {
+    let invalid = || format!("Invalid date '{}' (expected YYYY-MM-DD on or after 1970-01-01)", text);
+    let parts: Vec<&str> = text.trim().split('-').collect();
+    let [year, month, day] = parts[..] else { return Err(invalid()) };
+    let (Ok(year), Ok(month), Ok(day)) = (year.parse::<i64>(), month.parse::<i64>(), day.parse::<i64>()) else {
+        return Err(invalid());
+    };
+    if year < 1970 || !(1..=12).contains(&month) || day < 1 {
+        return Err(invalid());
+    }
+
+    // Civil-to-days conversion, the inverse of `calendar_date`
+    let shifted_year = if month <= 2 { year - 1 } else { year };
+    let era = shifted_year.div_euclid(400);
+    let year_of_era = shifted_year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    let secs = days as u64 * DAY;
+
+    // Reject days past the end of the month (e.g. 2024-02-30)
+    if calendar_date(secs) != format!("{:04}-{:02}-{:02}", year, month, day) {
+        return Err(invalid());
+    }
+    Ok(secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes a log of `count` games, alternating won and lost, oldest first
+    fn seeded_log(name: &str, count: u32) -> Vec<GameRecord> {
+        let dir = std::env::temp_dir().join(format!("guessing-game-{}-{}", name, std::process::id()));
+        let path = history_path(&dir);
+        let _ = fs::remove_file(&path);
+        for i in 0..count {
+            let game = GameRecord { timestamp: 1_700_000_000 + u64::from(i) * DAY, difficulty: Difficulty::Medium, won: i % 2 == 0, attempts: i + 1 };
+            append_game(&path, game).unwrap();
+        }
+        let games = load_history(&path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        games
+    }
+
+    #[test]
+    fn won_filter_keeps_only_wins_newest_first() {
+        let games = seeded_log("won-filter", 5);
+        let filter = HistoryFilter { won_only: true, ..HistoryFilter::default() };
+        let attempts: Vec<u32> = filter.apply(&games).iter().map(|game| game.attempts).collect();
+        assert_eq!(attempts, [5, 3, 1]);
+    }
+
+    #[test]
+    fn limit_keeps_the_most_recent_games() {
+        let games = seeded_log("limit", 5);
+        let filter = HistoryFilter { limit: Some(2), ..HistoryFilter::default() };
+        let attempts: Vec<u32> = filter.apply(&games).iter().map(|game| game.attempts).collect();
+        assert_eq!(attempts, [5, 4]);
+    }
+
+    #[test]
+    fn calendar_date_is_utc() {
+        assert_eq!(calendar_date(0), "1970-01-01");
+        assert_eq!(calendar_date(1_709_164_800), "2024-02-29");
+    }
+}
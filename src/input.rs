@@ -0,0 +1,292 @@
+//! Shared input reader
+//!
+//! Every prompt reads through here so input handling stays consistent:
+//! - End-of-input detection
+//! - Optional idle timeout with a final warning
+//! - Scripted input (e.g. guesses read from a file)
+//! - Hidden (non-echoed) input for secrets (`stty` on Unix, the console
+//!   mode on Windows), with a warning when echo can't be turned off
+//! - A cap on line length, so endless input cannot exhaust memory
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{self, BufRead, IsTerminal, Read};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::thread;
+use std::time::Duration;
+use colored::Colorize;
+
+/// Why no more input can be read
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputEnd {
+    /// Stdin was closed (or could not be read)
+    Eof,
+    /// Nothing was typed within the idle timeout
+    Idle,
+}
+
+/// Line length limit used until `init` sets one
+const DEFAULT_MAX_LINE: usize = 1024;
+
+/// Idle timeout chosen at startup (None = wait forever)
+static IDLE_TIMEOUT: OnceLock<Option<Duration>> = OnceLock::new();
+/// How long the player gets after the idle warning before the game quits
+static IDLE_GRACE: OnceLock<Duration> = OnceLock::new();
+/// Longest line accepted from stdin, in bytes
+static MAX_LINE: OnceLock<usize> = OnceLock::new();
+/// Lines read by the background reader thread, used only when a timeout is set
+static LINES: OnceLock<Mutex<Receiver<Option<String>>>> = OnceLock::new();
+
+thread_local! {
+    /// Pre-recorded lines that replace stdin entirely when set
+    /// Kept per thread, so each test can script its own input
+    static SCRIPT: RefCell<Option<VecDeque<String>>> = const { RefCell::new(None) };
+}
+
+/// Feeds all future reads on this thread from the given lines instead of stdin
+/// Calling it again replaces the remaining lines
+/// Parameters:
+///   lines: lines to hand out in order; running out counts as end of input
+pub fn use_script(lines: Vec<String>) {
+    SCRIPT.with_borrow_mut(|script| *script = Some(lines.into()));
+}
+
+/// Whether input comes from a script rather than the player
+pub fn scripted() -> bool {
+    SCRIPT.with_borrow(Option::is_some)
+}
+
+/// Configures the reader; call once before the first prompt
+/// Parameters:
+///   idle_timeout: Option<Duration> - warn after this long without input
+///   idle_grace: Duration - quit this long after the warning
+///   max_line: usize - longest line accepted, in bytes
+pub fn init(idle_timeout: Option<Duration>, idle_grace: Duration, max_line: usize) {
+    let _ = IDLE_TIMEOUT.set(idle_timeout);
+    let _ = IDLE_GRACE.set(idle_grace);
+    let _ = MAX_LINE.set(max_line);
+}
+
+/// Reads one line of player input
+/// Returns:
+///   The raw line, or why input has ended
+pub fn read_line() -> Result<String, InputEnd> {
+    if let Some(line) = SCRIPT.with_borrow_mut(|script| script.as_mut().map(VecDeque::pop_front)) {
+        return line.ok_or(InputEnd::Eof);
+    }
+    match IDLE_TIMEOUT.get().copied().flatten() {
+        None => read_blocking(),
+        Some(timeout) => {
+            let lines = locked(LINES.get_or_init(|| Mutex::new(spawn_reader())));
+            let grace = IDLE_GRACE.get().copied().unwrap_or(timeout);
+            read_with_timeout(&lines, timeout, grace)
+        },
+    }
+}
+
+/// Reads one line without echoing it to the terminal
+/// Falls back to a normal read when stdin is not a terminal, so scripted
+/// input still works; a terminal that won't stop echoing gets a warning
+pub fn read_hidden_line() -> Result<String, InputEnd> {
+    let terminal = io::stdin().is_terminal();
+    let hide = terminal && set_echo(false);
+    if terminal && !hide {
+        println!("{}", "Could not hide your typing - the secret will be visible.".yellow());
+    }
+    let line = read_line();
+    if hide {
+        set_echo(true);
+        println!();
+    }
+    line
+}
+
+/// Turns terminal echo on or off
+/// Returns:
+///   Whether the terminal accepted the change
+#[cfg(not(windows))]
+fn set_echo(on: bool) -> bool {
+    use std::process::{Command, Stdio};
+    Command::new("stty")
+        .arg(if on { "echo" } else { "-echo" })
+        .stdin(Stdio::inherit())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Turns console echo on or off
+/// Returns:
+///   Whether the console accepted the change
+#[cfg(windows)]
+fn set_echo(on: bool) -> bool {
+    use std::ffi::c_void;
+    use std::os::windows::io::AsRawHandle;
+    const ENABLE_ECHO_INPUT: u32 = 0x0004;
+    unsafe extern "system" {
+        fn GetConsoleMode(handle: *mut c_void, mode: *mut u32) -> i32;
+        fn SetConsoleMode(handle: *mut c_void, mode: u32) -> i32;
+    }
+    let handle = io::stdin().as_raw_handle();
+    let mut mode = 0;
+    // SAFETY: the handle is this process's stdin and `mode` outlives both calls
+    unsafe {
+        if GetConsoleMode(handle, &mut mode) == 0 {
+            return false;
+        }
+        let mode = if on { mode | ENABLE_ECHO_INPUT } else { mode & !ENABLE_ECHO_INPUT };
+        SetConsoleMode(handle, mode) != 0
+    }
+}
+
+/// Cleans up a raw input line so every prompt parses it the same way
+/// Strips surrounding whitespace, including Windows `\r\n` line endings
+/// Parameters:
+///   raw: &str - line as read
+/// Returns:
+///   The normalized text
+pub fn normalize_input(raw: &str) -> String {
+    raw.trim_end_matches(['\n', '\r']).trim().to_string()
+}
+
+/// Reads every line of a script (a guesses file or piped stdin)
+/// Each line goes through the same length cap as a typed line
+/// Returns:
+///   The lines without their endings; over-long lines come back empty
+pub fn read_lines(mut reader: impl BufRead) -> Vec<String> {
+    let max_line = max_line();
+    std::iter::from_fn(|| read_capped(&mut reader, max_line))
+        .map(|line| line.trim_end_matches(['\n', '\r']).to_string())
+        .collect()
+}
+
+/// Longest line accepted, as set by `init`
+fn max_line() -> usize {
+    MAX_LINE.get().copied().unwrap_or(DEFAULT_MAX_LINE)
+}
+
+/// Plain blocking read straight from stdin
+fn read_blocking() -> Result<String, InputEnd> {
+    read_capped(&mut io::stdin().lock(), max_line()).ok_or(InputEnd::Eof)
+}
+
+/// Reads one line, never buffering more than the line length limit
+/// An over-long line is skipped to its end and comes back empty, so
+/// prompts treat it as invalid input
+/// Parameters:
+///   reader: &mut impl BufRead - where the line comes from
+///   max_line: usize - longest line accepted, in bytes
+/// Returns:
+///   The line, or None at end of input (or on unreadable / non-UTF-8 input)
+fn read_capped(reader: &mut impl BufRead, max_line: usize) -> Option<String> {
+    let mut bytes = Vec::new();
+    match reader.by_ref().take(max_line as u64 + 1).read_until(b'\n', &mut bytes) {
+        Ok(0) | Err(_) => return None,
+        Ok(_) => {},
+    }
+    if bytes.len() > max_line && !bytes.ends_with(b"\n") {
+        let _ = reader.skip_until(b'\n');
+        println!("{}", format!("Input too long (over {} bytes) - ignored.", max_line).red());
+        return Some(String::new());
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Locks shared input state, recovering it if a panicking thread held it
+/// (a queue of lines is still usable after a panic mid-read)
+fn locked<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Waits for a line, warning once before giving up on an idle player
+/// Parameters:
+///   lines: &Receiver<Option<String>> - lines from the reader thread (None at end of input)
+///   timeout: Duration - silence allowed before the warning
+///   grace: Duration - silence allowed after the warning
+fn read_with_timeout(lines: &Receiver<Option<String>>, timeout: Duration, grace: Duration) -> Result<String, InputEnd> {
+    match lines.recv_timeout(timeout) {
+        Ok(line) => return line.ok_or(InputEnd::Eof),
+        Err(RecvTimeoutError::Disconnected) => return Err(InputEnd::Eof),
+        Err(RecvTimeoutError::Timeout) => {},
+    }
+
+    println!("{}", format!("Still there? Quitting in {}s...", grace.as_secs_f64()).yellow());
+    match lines.recv_timeout(grace) {
+        Ok(line) => line.ok_or(InputEnd::Eof),
+        Err(RecvTimeoutError::Disconnected) => Err(InputEnd::Eof),
+        Err(RecvTimeoutError::Timeout) => Err(InputEnd::Idle),
+    }
+}
+
+/// Starts a thread that forwards stdin lines, sending None at end of input
+fn spawn_reader() -> Receiver<Option<String>> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let stdin = io::stdin();
+        loop {
+            let next = read_capped(&mut stdin.lock(), max_line());
+            let finished = next.is_none();
+            if sender.send(next).is_err() || finished {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn poisoned_lock_is_recovered() {
+        let script = Mutex::new(VecDeque::from([String::from("42")]));
+        let _ = thread::scope(|scope| {
+            scope.spawn(|| {
+                let _guard = script.lock().unwrap();
+                panic!("reader died");
+            }).join()
+        });
+        assert!(script.is_poisoned());
+        assert_eq!(locked(&script).pop_front().as_deref(), Some("42"));
+    }
+
+    #[test]
+    fn line_endings_and_padding_are_normalized() {
+        assert_eq!(normalize_input("50\r\n"), "50");
+        assert_eq!(normalize_input("  2  \n"), "2");
+        assert_eq!(normalize_input("\t7\r"), "7");
+        assert_eq!(normalize_input("\r\n"), "");
+    }
+
+    #[test]
+    fn silent_player_is_timed_out_after_the_grace_period() {
+        let (sender, lines) = mpsc::channel();
+        let short = Duration::from_millis(20);
+        assert_eq!(read_with_timeout(&lines, short, short), Err(InputEnd::Idle));
+        // A line that arrives in time is still read, and a closed reader is the end of input
+        sender.send(Some(String::from("42"))).unwrap();
+        assert_eq!(read_with_timeout(&lines, short, short).as_deref(), Ok("42"));
+        drop(sender);
+        assert_eq!(read_with_timeout(&lines, short, short), Err(InputEnd::Eof));
+    }
+
+    #[test]
+    fn over_long_line_is_rejected_and_skipped() {
+        let mut text = "9".repeat(5000);
+        text.push_str("\n42\n");
+        let mut reader = io::Cursor::new(text);
+        // The long line comes back empty, so prompts treat it as invalid
+        assert_eq!(read_capped(&mut reader, 16).as_deref(), Some(""));
+        // Its tail was skipped, and the next line reads normally
+        assert_eq!(read_capped(&mut reader, 16).as_deref(), Some("42\n"));
+        assert_eq!(read_capped(&mut reader, 16), None);
+    }
+
+    #[test]
+    fn line_at_the_cap_is_accepted() {
+        let mut reader = io::Cursor::new(format!("{}\n", "7".repeat(16)));
+        assert_eq!(read_capped(&mut reader, 16), Some(format!("{}\n", "7".repeat(16))));
+        assert_eq!(read_lines(io::Cursor::new(format!("1\r\n{}\n3", "x".repeat(5000)))), ["1", "", "3"]);
+    }
+}
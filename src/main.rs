@@ -1,89 +1,711 @@
 //! Main entry point for the number guessing game
 //! 
 //! This implements a complete guessing game where:
-//! - A random number between 1.0-100.0 is generated
+//! - A random number is generated in the chosen range (1-100 by default,
+//!   or from a difficulty, range preset, band or dice)
 //! - Players guess with optional hints
 //! - Supports retrying with same or new numbers
 
-use std::io;
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
 use std::process::exit;
-use crate::utils::{game_loop, gen_rand, end_situation_handler, choose_hint, game_range_adjuster};
+use std::thread;
+use colored::Colorize;
+use rand::Rng;
+use rand::seq::IndexedRandom;
+use rand::rngs::StdRng;
+use crate::certificate::{GameSummary, make_certificate, verify_certificate};
+use crate::config::{SEED_ENV, Command, Difficulty, GameConfig, GameMode, GameRange, HintCategory, TimeoutReveal, infer_mode, parse_args, validate_tolerance};
+use crate::error::GameError;
+use crate::history::{GameRecord, append_game, history_path, history_table, load_history};
+use crate::input::normalize_input;
+use crate::menu::{choice_hint, retry_menu};
+use crate::round::{LUCKY_BONUS, RoundResult, RoundState, analyze_search, attempt_times, compute_score, is_lucky, next_difficulty, result_line, tighten_range, trace_lines};
+use crate::simulate::simulate;
+use crate::scoreboard::{Scoreboard, challenge_code, load_scores, save_scores, scoreboard_path};
+use crate::stats::{PersistedStats, SessionStats, Speedrun, export_heatmap, export_markdown, load_stats, merge_stats_files, personal_bests_report, save_stats, startup_banner, stats_path, weaknesses_report};
+use crate::twist::random_twist;
+use crate::utils::{auto_solve, auto_solve_steps, narrate_steps, reveal_breakdown, make_winnable, game_loop, gen_secret, seeded_rng, end_of_round_message, end_situation_handler, choose_hint, format_number, game_range_adjuster, hint_previews, dump_hints, parse_range_bounds, timeout_reveal_line, HintChoice, HintDeck};
+mod certificate;
+mod config;
+mod error;
+mod expr;
+mod history;
+mod input;
+mod json;
+mod menu;
+mod round;
+mod scoreboard;
+mod simulate;
+mod stats;
+mod trainer;
+mod twist;
 mod utils;
 
 fn main() {
+    // Read command-line options before anything is printed
+    let mut config = match parse_args(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e.to_string().red());
+            exit(2);
+        }
+    };
+    apply_seed_env(&mut config);
+    // Dry run: show what the merged settings resolved to
+    if config.print_config {
+        print!("{}", config.to_config_text());
+        exit(0);
+    }
+    if config.dump_hints {
+        let secret = config.dump_secret.unwrap_or((config.range_start + config.range_end) / 2.0);
+        println!("Hint pool for S = {}", format_number(secret));
+        for line in dump_hints(secret) {
+            println!("{}", line);
+        }
+        exit(0);
+    }
+    if let Command::StatsMerge(other) = &config.command {
+        match merge_stats_files(&stats_path(&config.data_dir), other) {
+            Ok(merged) => {
+                println!("Merged {} into local stats: {} games, {} wins.", other.display(), merged.games, merged.wins);
+                exit(0);
+            },
+            Err(e) => {
+                eprintln!("{}", format!("Merge failed, local stats left untouched: {}", e).red());
+                exit(1);
+            }
+        }
+    }
+
+    if let Command::History(filter) = &config.command {
+        match load_history(&history_path(&config.data_dir)) {
+            Ok(games) => {
+                let shown = filter.apply(&games);
+                if shown.is_empty() {
+                    println!("No games match.");
+                } else {
+                    print!("{}", history_table(&shown));
+                }
+                exit(0);
+            },
+            Err(e) => {
+                eprintln!("{}", format!("Could not read the game history: {}", e).red());
+                exit(1);
+            }
+        }
+    }
+
+    if let Command::Verify(certificate) = &config.command {
+        match verify_certificate(certificate) {
+            Ok(summary) => {
+                println!("{}", "Certificate verified.".green());
+                println!("{}", summary);
+                exit(0);
+            },
+            Err(e) => {
+                eprintln!("{}", e.to_string().red());
+                exit(1);
+            }
+        }
+    }
+
+    if let Command::Weaknesses = config.command {
+        match load_stats(&stats_path(&config.data_dir)) {
+            Ok(all_time) => {
+                print!("{}", weaknesses_report(&all_time));
+                exit(0);
+            },
+            Err(e) => {
+                eprintln!("{}", format!("Could not load stats: {}", e).red());
+                exit(1);
+            }
+        }
+    }
+
+    if let Command::Compare { seed_a, seed_b } = config.command {
+        compare_seeds(&config, seed_a, seed_b);
+        exit(0);
+    }
+
+    if config.demo {
+        run_demo(&config);
+        exit(0);
+    }
+
+    if let Command::Simulate(plan) = config.command {
+        let (_, mut rng) = session_rng(&config);
+        println!(
+            "Simulating {} games by the {} over {}",
+            plan.games,
+            plan.player.name(),
+            config.game_range(config.range_start, config.range_end)
+        );
+        print!("{}", simulate(&config, plan, &mut rng).describe());
+        exit(0);
+    }
+
+    input::init(config.idle_timeout, config.idle_grace, config.max_line);
+    if let Command::Trainer = config.command {
+        let (_, mut rng) = session_rng(&config);
+        match trainer::run(&config, &mut rng) {
+            Ok(score) => println!("\nFinal trainer score: {}/{}", score.correct, score.asked),
+            Err(e) => {
+                eprintln!("{}", format!("Something went wrong: {}", e).red());
+                exit(1);
+            }
+        }
+        exit(0);
+    }
+    let mut stats = SessionStats::default();
+    match load_stats(&stats_path(&config.data_dir)) {
+        Ok(all_time) => {
+            stats.current_streak = all_time.current_streak;
+            stats.all_time = all_time;
+        },
+        Err(e) => eprintln!("{}", format!("Could not load stats: {}", e).red()),
+    }
+    if config.difficulty.locked(stats.all_time.hard_wins, config.unlock_wins) {
+        let label = config.difficulty.menu_label(stats.all_time.hard_wins, config.unlock_wins);
+        eprintln!("{}", format!("Cannot play {}", label).red());
+        eprintln!("Difficulties:");
+        for difficulty in Difficulty::ALL {
+            eprintln!("  {}", difficulty.menu_label(stats.all_time.hard_wins, config.unlock_wins));
+        }
+        exit(2);
+    }
+    let mut scoreboard = match load_scores(&scoreboard_path(&config.data_dir)) {
+        Ok(scoreboard) => scoreboard,
+        Err(e) => {
+            eprintln!("{}", format!("Could not load the scoreboard: {}", e).red());
+            Scoreboard::default()
+        }
+    };
+
+    // Piped quick-start: first line is the range, the rest are guesses
+    if config.stdin_range {
+        let lines = match read_stdin_range(&mut config, std::io::stdin().lock()) {
+            Ok(lines) => lines,
+            Err(message) => {
+                eprintln!("{}", message.red());
+                exit(2);
+            }
+        };
+        input::use_script(lines);
+        if let Err(e) = play_scripted(&config, &mut stats, &mut scoreboard) {
+            quit_on_error(e, &stats, &config);
+        }
+        exit_game(&stats, &config);
+    }
+
+    // Batch mode: play one round from a file of guesses, no prompts
+    if let Some(path) = &config.guesses_from {
+        match File::open(path) {
+            Ok(file) => input::use_script(input::read_lines(BufReader::new(file))),
+            Err(e) => {
+                eprintln!("{}", format!("Could not read {}: {}", path.display(), e).red());
+                exit(2);
+            }
+        }
+        if let Err(e) = play_scripted(&config, &mut stats, &mut scoreboard) {
+            quit_on_error(e, &stats, &config);
+        }
+        exit_game(&stats, &config);
+    }
+
     // Print game introduction
     println!("Welcome to the Fantastic Number Guessing Game!");
     println!("=============================================");
-    println!("A random number between 1.0 and 100.0 will be generated.");
+    println!("A random number between {:.1} and {:.1} will be generated.", config.range_start, config.range_end);
     println!("Try to guess it with optional hints to help you!\n");
+    if !config.quiet {
+        println!("{}\n", startup_banner(&stats.all_time).bold());
+    }
+    print_speedrun_pb(&stats.all_time, config.difficulty);
+    let records = scoreboard.summary_lines();
+    if !records.is_empty() {
+        println!("{}", "Records:".bold());
+        for line in records {
+            println!("  {}", line);
+        }
+        println!();
+    }
+
+    // Play until the player quits; running out of input also ends the session
+    if let Err(e) = play(&config, &mut stats, &mut scoreboard) {
+        quit_on_error(e, &stats, &config);
+    }
+}
+
+/// Reads piped input for `--stdin-range`, applying the first line as the range
+/// Lines are read with the usual length cap, so a huge pipe can't exhaust memory
+/// Parameters:
+///   config: &mut GameConfig - receives the range (and the inferred mode, if none was set)
+///   reader: impl BufRead - the piped input (stdin)
+/// Returns:
+///   The remaining lines (the guesses), or why the range line was rejected
+fn read_stdin_range(config: &mut GameConfig, reader: impl BufRead) -> Result<Vec<String>, String> {
+    let mut lines = input::read_lines(reader).into_iter();
+    let first = normalize_input(&lines.next().unwrap_or_default());
+    let bounds: Vec<&str> = first.split(|c: char| c.is_whitespace() || c == ',').filter(|part| !part.is_empty()).collect();
+    let [start, end] = bounds[..] else {
+        return Err(format!("--stdin-range expects \"START END\" on the first line, got \"{}\"", first));
+    };
+    let mode = if config.explicit_mode { config.mode } else { GameMode::Decimal };
+    let (start, end) = parse_range_bounds(start, end, config.max_span, mode)?;
+    config.range_start = start;
+    config.range_end = end;
+    if !config.explicit_mode {
+        config.mode = infer_mode(start, end);
+    }
+    validate_tolerance(config.tolerance, start, end)?;
+    Ok(lines.collect())
+}
+
+/// Auto-solves the first secret of two seeds and reports which is harder
+/// Parameters:
+///   config: &GameConfig - range and mode to generate secrets in
+///   seed_a: u64 - first seed
+///   seed_b: u64 - second seed
+fn compare_seeds(config: &GameConfig, seed_a: u64, seed_b: u64) {
+    let range = (config.range_start, config.range_end);
+    let mut config = config.clone();
+    make_winnable(&mut config, range);
+    let config = &config;
+    println!("Comparing seeds over {}", config.game_range(range.0, range.1));
+    let mut counts = Vec::new();
+    for seed in [seed_a, seed_b] {
+        let secret = gen_secret(&mut seeded_rng(seed), range.0, range.1, config);
+        let guesses = auto_solve(secret, range, config).len();
+        println!("Seed {}: secret {}, solved in {} guess(es)", seed, format_number(secret), guesses);
+        counts.push(guesses);
+    }
+    match counts[0].cmp(&counts[1]) {
+        std::cmp::Ordering::Greater => println!("Seed {} is harder.", seed_a),
+        std::cmp::Ordering::Less => println!("Seed {} is harder.", seed_b),
+        std::cmp::Ordering::Equal => println!("Both seeds are equally hard."),
+    }
+}
+
+/// Seeds the game from `GUESSINGGAME_SEED` when no seed was given
+fn apply_seed_env(config: &mut GameConfig) {
+    if let Some(warning) = seed_from_env(config, std::env::var(SEED_ENV).ok().as_deref()) {
+        eprintln!("{}", warning.yellow());
+    }
+}
+
+/// Applies a `GUESSINGGAME_SEED` value to the config
+/// A `--seed` flag (or config file entry) wins; a value that is not a
+/// whole number is ignored, leaving the seed random
+/// Parameters:
+///   config: &mut GameConfig - resolved settings
+///   value: Option<&str> - the variable's value, if it is set
+/// Returns:
+///   A warning when the value had to be ignored
+fn seed_from_env(config: &mut GameConfig, value: Option<&str>) -> Option<String> {
+    if config.seed.is_some() {
+        return None;
+    }
+    match value?.trim().parse() {
+        Ok(seed) => {
+            config.seed = Some(seed);
+            None
+        },
+        Err(_) => Some(format!("Ignoring {}={:?}: not a whole number, using a random seed.", SEED_ENV, value?)),
+    }
+}
+
+/// Shows the auto-solver finding the session's first secret, step by step
+/// With `--animate` each step waits for the configured delay, so the
+/// interval can be seen closing in; `--narrate` explains each step
+fn run_demo(config: &GameConfig) {
+    let (_, mut rng) = session_rng(config);
+    let range = (config.range_start, config.range_end);
+    let mut config = config.clone();
+    if make_winnable(&mut config, range) {
+        announce_tolerance(&config);
+    }
+    let config = &config;
+    let secret = gen_secret(&mut rng, range.0, range.1, config);
+    println!("Solver demo over {}: the secret is {}", config.game_range(range.0, range.1), format_number(secret));
+    let steps = auto_solve_steps(secret, range, config);
+    let narration = narrate_steps(&steps, secret, config.tolerance);
+    for (i, step) in steps.iter().enumerate() {
+        if config.animate && i > 0 {
+            thread::sleep(config.demo_delay);
+        }
+        if config.narrate {
+            let (reasoning, reaction) = &narration[i];
+            println!("Step {}: {}", i + 1, reasoning);
+            println!("        {}", reaction);
+            continue;
+        }
+        let verdict = if (step.guess - secret).abs() <= config.tolerance {
+            "correct!"
+        } else if step.guess < secret {
+            "too small"
+        } else {
+            "too big"
+        };
+        println!(
+            "Step {}: {}–{} → guess {} ({})",
+            i + 1,
+            format_number(step.low),
+            format_number(step.high),
+            format_number(step.guess),
+            verdict
+        );
+    }
+    println!("Solved in {} guess(es).", steps.len());
+}
+
+/// Ends the session after an error, keeping the stats gathered so far
+/// Running out of input is a normal way to stop; anything else exits non-zero
+fn quit_on_error(error: GameError, stats: &SessionStats, config: &GameConfig) -> ! {
+    exit(wind_down(&error, stats, config));
+}
+
+/// Saves the session after an error, the way a normal quit would when
+/// input ran out (closed or idle)
+/// Returns:
+///   The exit code for the error
+fn wind_down(error: &GameError, stats: &SessionStats, config: &GameConfig) -> i32 {
+    match error {
+        GameError::Input(_) => {
+            println!("\n{} Quitting.", error);
+            finish_session(stats, config);
+        },
+        _ => {
+            eprintln!("\n{}", format!("Something went wrong: {}", error).red());
+            persist(stats, || save_stats(&stats_path(&config.data_dir), &stats.all_time));
+        },
+    }
+    error.exit_code()
+}
+
+/// Runs games until the player quits
+/// Returns:
+///   Err when player input ends (closed or idle) before they quit
+fn play(session_config: &GameConfig, stats: &mut SessionStats, scoreboard: &mut Scoreboard) -> Result<(), GameError> {
+    // One seeded generator drives every secret in the session
+    let (mut seed, mut rng) = session_rng(session_config);
 
     // Track the current guessing range
     let mut range_start: f64;
     let mut range_end: f64;
 
+    // Last range picked with the range adjuster, for `sticky_range`
+    let mut sticky_range: Option<(f64, f64)> = None;
+
+    // Only the first secret after a chosen seed is a shareable challenge
+    let mut fresh_seed = session_config.seed.is_some();
+
+    // Adaptive difficulty: outcome of each finished game, and the current level
+    let mut recent: Vec<RoundResult> = Vec::new();
+    let mut difficulty = session_config.difficulty;
+    // Set once the player picks a difficulty from the retry menu
+    let mut difficulty_chosen = false;
+
+    // Escalating mode: range for the next game after a run of wins
+    let mut escalated: Option<(f64, f64)> = None;
+
     // Main game loop - runs until player chooses to quit
     'game: loop {
-        // Always reset range to default at the start of a new game
-        range_start = 1.0;
-        range_end = 100.0;
+        // A lost game puts an escalating range back to normal
+        if recent.last().is_some_and(|result| !result.won) && escalated.take().is_some() {
+            println!("{}", "Range reset after the loss.".cyan());
+        }
+        // Adapt the difficulty to the games finished so far
+        if session_config.adaptive {
+            let next = next_difficulty(difficulty, &recent);
+            if next != difficulty && !next.locked(stats.all_time.hard_wins, session_config.unlock_wins) {
+                let verb = if next > difficulty { "raised" } else { "lowered" };
+                println!("{}", format!("\nDifficulty {} to {}", verb, next).cyan().bold());
+                print_speedrun_pb(&stats.all_time, next);
+                difficulty = next;
+                recent.clear();
+            }
+        }
+
+        // Settings for this game: adaptive or chosen difficulty, then any band
+        let mut game_config = game_settings(session_config, difficulty, difficulty_chosen);
+        let band = pick_band(&mut game_config, &session_config.bands, &mut rng);
+        if let Some(label) = &band {
+            println!("\n{}", format!("Band: {}", label).cyan().bold());
+        }
+        // A new game starts from the configured range, unless sticky ranges
+        // keep the last one chosen with the range adjuster
+        (range_start, range_end) = match sticky_range {
+            Some(range) if game_config.sticky_range => range,
+            _ => (game_config.range_start, game_config.range_end),
+        };
+        if let Some(range) = escalated {
+            (range_start, range_end) = range;
+        }
+
+        // Surprise twists go on last, once the range they must fit is known
+        if session_config.surprise {
+            let twist = random_twist(&mut rng);
+            match twist.apply(&game_config, (range_start, range_end)) {
+                Some(twisted) => {
+                    println!("\n{}", format!("Twist: {}", twist.name()).magenta().bold());
+                    game_config = twisted;
+                },
+                None => println!("\n{}", format!("Twist: {} doesn't fit this range - playing it straight.", twist.name()).magenta()),
+            }
+        }
+
+        if make_winnable(&mut game_config, (range_start, range_end)) {
+            announce_tolerance(&game_config);
+        }
+        let config = &game_config;
 
         // Optionally allow user to adjust range before the game starts
         // (Uncomment the next two lines if you want to prompt every time)
-        // let (new_start, new_end) = game_range_adjuster();
+        // let (new_start, new_end) = game_range_adjuster(config.max_span, config.mode)?;
         // range_start = new_start; range_end = new_end;
 
         // Generate new secret number for each game session
-        let secret_number = gen_rand(range_start, range_end);
-        let mut attempts = 0;
+        let mut secret_number = if config.set_secret {
+            prompt_secret((range_start, range_end), config)?
+        } else {
+            gen_secret(&mut rng, range_start, range_end, config)
+        };
+        let mut round = RoundState::new(config.target, config.attempt_ceiling);
+        if config.hint_deck {
+            round.hint_deck = Some(HintDeck::new(rng.random()));
+        }
+        if fresh_seed && !config.set_secret {
+            round.challenge = Some(challenge_code(seed, (range_start, range_end), config.mode));
+        }
+        fresh_seed = false;
+        round.band = band;
         println!("\nNew game started! A secret number has been generated.");   
+        announce_challenge_best(scoreboard, &round);
 
         // Retry loop - allows playing same number multiple times
         'retry: loop {
             // Get player's hint preference
-            let hint_choice = get_hint_choice();
+            let hint_choice = get_hint_choice(&round, (range_start, range_end), config)?;
             
             // Show selected hint type
-            choose_hint(&hint_choice, secret_number);
+            choose_hint(hint_choice, secret_number, &mut round, (range_start, range_end), config, &mut rng);
 
             // Run one full game round and get results
-            let (guess_correct, new_attempts) = game_loop(secret_number, attempts);
-            attempts = new_attempts;
+            let guessed = round.trace().len();
+            let guess_correct = game_loop(secret_number, &mut round, (range_start, range_end), config)?;
+            for event in &round.trace()[guessed..] {
+                stats.all_time.record_guess(event.value, (range_start, range_end));
+            }
+            // All-time average from before this game, for the win summary
+            let average = stats.all_time.avg_attempts();
+            // A game ends with a win or when time or points run out; other
+            // misses stay part of it until the player moves on (see `leave_game`)
+            if guess_correct || round.timed_out || round.out_of_points {
+                if guess_correct && config.escalating && !round.continued {
+                    let tightened = tighten_range((range_start, range_end), rng.random(), config.mode);
+                    if tightened != (range_start, range_end) {
+                        println!("{}", format!("Range tightened to {}–{}", format_number(tightened.0), format_number(tightened.1)).cyan().bold());
+                    }
+                    escalated = Some(tightened);
+                }
+                if config.certificate {
+                    let summary = GameSummary {
+                        seed,
+                        range: (range_start, range_end),
+                        secret: secret_number,
+                        tolerance: config.tolerance,
+                        guesses: round.trace().iter().map(|event| event.value).collect(),
+                        won: guess_correct,
+                    };
+                    println!("Certificate: {}", make_certificate(&summary));
+                }
+                recent.extend(record_game(stats, config, &mut round, secret_number, (range_start, range_end), guess_correct));
+            }
+            if config.show_trace {
+                print_trace(&round);
+            }
+
+            // Running out of time or points ends this number; move on to a new one
+            if round.timed_out || round.out_of_points {
+                end_of_round_message(false, round.attempts);
+                // Running out of points always shows the answer; timeouts follow --timeout-reveal
+                let reveal = if round.timed_out { config.timeout_reveal } else { TimeoutReveal::Secret };
+                if let Some(line) = timeout_reveal_line(secret_number, (range_start, range_end), reveal) {
+                    println!("{}", line);
+                }
+                continue 'game;
+            }
+
+            // Score the win, checking the personal target if one was set
+            let mut score = 0;
+            if guess_correct {
+                let previous_best = round.best_run;
+                round.best_run = Some(previous_best.map_or(round.attempts, |best| best.min(round.attempts)));
+                if let Some(best) = previous_best {
+                    if round.attempts < best {
+                        println!("{}", format!("Beat your best run on this number ({} → {} attempts)!", best, round.attempts).green().bold());
+                    } else {
+                        println!("Best run on this number: {} attempts.", best);
+                    }
+                }
+                print_attempt_times(&round, config);
+                print_worst_guess(&round, secret_number, config);
+                print_average_comparison(round.attempts, average);
+                if let Some(win) = round.trace().last() {
+                    let run = Speedrun { time: win.at, attempts: round.attempts };
+                    if stats.all_time.record_speedrun(config.difficulty, run) {
+                        println!("{}", format!("New speedrun PB: {:.1}s!", win.at.as_secs_f64()).green().bold());
+                        autosave_stats(stats, config);
+                    }
+                }
+                let goal_met = round.goal_met();
+                match goal_met {
+                    Some(true) => println!("{}", "Goal met!".green().bold()),
+                    Some(false) => println!("{}", "Goal missed.".yellow()),
+                    None => {},
+                }
+                let lucky = check_lucky(secret_number, config);
+                score = compute_score(round.attempts, goal_met == Some(true), round.hint_count(), lucky);
+                stats.score += score;
+                println!("Round score: {} (session total: {})", score, stats.score);
+                if stats.all_time.record_score(score) {
+                    autosave_stats(stats, config);
+                }
+                record_to_scoreboard(stats, scoreboard, config, score, &round);
+                println!("{}", analyze_search(&round.guesses(), secret_number, range_start, range_end).describe());
+
+                // Optional double-or-nothing bonus round
+                if config.interactive() && prompt_wager(score)? {
+                    let allowed = (round.attempts - 1).max(1);
+                    let bonus_won = play_bonus_round(&mut rng, (range_start, range_end), allowed, config)?;
+                    stats.settle_wager(score, bonus_won);
+                    if bonus_won {
+                        println!("{}", format!("Wager won! Score doubled to {}.", score * 2).green().bold());
+                    } else {
+                        println!("{}", format!("Wager lost. {} points forfeited.", score).red());
+                    }
+                    println!("Session total: {}", stats.score);
+                }
+            }
+            if config.oneline {
+                println!("{}", result_line(guess_correct, round.attempts, seed, (range_start, range_end), score));
+            }
 
             // Handle post-game choices
-            match end_situation_handler(guess_correct, attempts) {
+            let next_step = if config.interactive() {
+                end_situation_handler(guess_correct, &round, config.bell && !config.quiet)?
+            } else {
+                // Without menus: keep guessing until the number is found, then stop
+                end_of_round_message(guess_correct, round.attempts);
+                i32::from(!guess_correct)
+            };
+            match next_step {
                 1 => { // Player wants to continue
-                    match get_retry_choice() {
+                    // Mercy rule: offer the answer after too many tries on one secret
+                    if !guess_correct
+                        && !round.reveal_offered
+                        && config.interactive()
+                        && config.reveal_after.is_some_and(|limit| round.total_attempts >= limit)
+                    {
+                        round.reveal_offered = true;
+                        if prompt_reveal(round.total_attempts)? {
+                            recent.extend(leave_game(stats, config, &mut round, secret_number, (range_start, range_end)));
+                            println!("The number was {}.", format_number(secret_number));
+                            if !config.quiet {
+                                for line in reveal_breakdown(secret_number, (range_start, range_end), config) {
+                                    println!("  {}", line);
+                                }
+                            }
+                            continue 'game;
+                        }
+                    }
+                    match get_retry_choice(config, guess_correct)? {
                         1 => { // Retry same number
-                            println!("\nContinuing with same number. Attempts reset.");
+                            round.retry_same_number(config.carry_attempts);
+                            if config.carry_attempts {
+                                println!("\nContinuing with same number. Attempts carry over ({} so far).", round.attempts);
+                            } else {
+                                println!("\nContinuing with same number. Attempts reset.");
+                            }
+                            continue 'retry;
+                        },
+                        5 => { // Same number as a continuation of this game
+                            round.attempts = 0;
+                            println!("\nContinuing with same number. Attempts reset; this still counts as one game in your stats.");
                             continue 'retry;
                         },
-                        2 => { // Get new number (will reset range at top of 'game' loop)
+                        6 => { // Replay the number just won, aiming for fewer attempts
+                            round.attempts = 0;
+                            round.continued = true;
+                            println!("\nReplaying the same number. Best run so far: {} attempts.", round.best_run.unwrap_or(0));
+                            continue 'retry;
+                        },
+                        2 => { // Get new number (range resets at top of 'game' loop unless sticky)
+                            recent.extend(leave_game(stats, config, &mut round, secret_number, (range_start, range_end)));
                             println!("\nGenerating new number...");
                             continue 'game;
                         },
                         3 => {
                             println!("\nPreparing the game-range adjuster");
-                            let (new_start, new_end) = game_range_adjuster();
-                            range_start = new_start;
-                            range_end = new_end;
-                            println!("New guessing range set: {:.1} to {:.1}", range_start, range_end);
-                            // Generate new secret number with new range, but do not reset to default until next 'game'
-                            let secret_number = gen_rand(range_start, range_end);
-                            attempts = 0;
+                            recent.extend(leave_game(stats, config, &mut round, secret_number, (range_start, range_end)));
+                            // The new range keeps until the next 'game' (or longer, if sticky)
+                            let (new_range, new_secret) = change_range(config, &mut rng)?;
+                            (range_start, range_end) = new_range;
+                            sticky_range = Some(new_range);
+                            secret_number = new_secret;
+                            round = RoundState::new(config.target, config.attempt_ceiling);
+                            if config.hint_deck {
+                                round.hint_deck = Some(HintDeck::new(rng.random()));
+                            }
                             continue 'retry;
                         },
+                        4 => { // Reseed just the next number
+                            let new_seed = prompt_seed()?;
+                            recent.extend(leave_game(stats, config, &mut round, secret_number, (range_start, range_end)));
+                            seed = new_seed;
+                            rng = seeded_rng(new_seed);
+                            secret_number = gen_secret(&mut rng, range_start, range_end, config);
+                            round = RoundState::new(config.target, config.attempt_ceiling);
+                            if config.hint_deck {
+                                round.hint_deck = Some(HintDeck::new(rng.random()));
+                            }
+                            round.challenge = Some(challenge_code(seed, (range_start, range_end), config.mode));
+                            println!("\nReseeded with {}. A new secret number has been generated.", new_seed);
+                            announce_challenge_best(scoreboard, &round);
+                            continue 'retry;
+                        },
+                        7 => { // Pick a difficulty for the next game
+                            recent.extend(leave_game(stats, config, &mut round, secret_number, (range_start, range_end)));
+                            if let Some(next) = prompt_difficulty(difficulty, &stats.all_time, config.unlock_wins)? {
+                                println!("{}", format!("\nDifficulty changed to {}", next).cyan().bold());
+                                print_speedrun_pb(&stats.all_time, next);
+                                difficulty = next;
+                                difficulty_chosen = true;
+                                // The new preset replaces any kept or tightened range
+                                sticky_range = None;
+                                escalated = None;
+                                recent.clear();
+                            }
+                            continue 'game;
+                        },
                         0 => { // Quit game
-                            println!("\nGame over in {} attempts. -quiting", attempts);
-                            exit_game();
+                            println!("\nGame over in {} attempts. -quiting", round.attempts);
+                            leave_game(stats, config, &mut round, secret_number, (range_start, range_end));
+                            exit_game(stats, config);
                         },
                         _ => { // Invalid input
                             println!("Invalid choice. Starting new game.");
+                            recent.extend(leave_game(stats, config, &mut round, secret_number, (range_start, range_end)));
                             continue 'game;
                         }
                     }
                 },
                 0 => { // Player chose to quit
-                    exit_game();
+                    leave_game(stats, config, &mut round, secret_number, (range_start, range_end));
+                    exit_game(stats, config);
                 },
                 _ => { // Error case
                     println!("Unexpected error. Exiting.");
@@ -94,39 +716,715 @@ fn main() {
     }
 }
 
+/// Plays one round using only scripted guesses
+/// Running out of guesses before a win counts as a loss
+fn play_scripted(config: &GameConfig, stats: &mut SessionStats, scoreboard: &mut Scoreboard) -> Result<(), GameError> {
+    let (seed, mut rng) = session_rng(config);
+    let range = (config.range_start, config.range_end);
+    let mut config = config.clone();
+    if make_winnable(&mut config, range) {
+        announce_tolerance(&config);
+    }
+    let config = &config;
+    let secret_number = gen_secret(&mut rng, range.0, range.1, config);
+    let mut round = RoundState::new(config.target, config.attempt_ceiling);
+    if config.seed.is_some() {
+        round.challenge = Some(challenge_code(seed, range, config.mode));
+    }
+    announce_challenge_best(scoreboard, &round);
+
+    let won = loop {
+        match game_loop(secret_number, &mut round, range, config) {
+            Ok(true) => break true,
+            // Running out of time or points ends a scripted game too
+            Ok(false) if round.timed_out || round.out_of_points => break false,
+            Ok(false) => continue,
+            Err(GameError::Input(_)) => {
+                println!("{}", "Ran out of scripted guesses.".red());
+                break false;
+            },
+            Err(e) => return Err(e),
+        }
+    };
+
+    let score = if won {
+        let lucky = check_lucky(secret_number, config);
+        compute_score(round.attempts, round.goal_met() == Some(true), round.hint_count(), lucky)
+    } else {
+        0
+    };
+    record_game(stats, config, &mut round, secret_number, range, won);
+    if config.show_trace {
+        print_trace(&round);
+    }
+    stats.score += score;
+    if won {
+        stats.all_time.record_score(score);
+    }
+    end_of_round_message(won, round.attempts);
+    print_attempt_times(&round, config);
+    print_worst_guess(&round, secret_number, config);
+    if won {
+        record_to_scoreboard(stats, scoreboard, config, score, &round);
+    }
+    println!("{}", analyze_search(&round.guesses(), secret_number, range.0, range.1).describe());
+    if config.oneline {
+        println!("{}", result_line(won, round.attempts, seed, range, score));
+    }
+    Ok(())
+}
+
+/// Tells the player how close a decimal guess has to be to win
+fn announce_tolerance(config: &GameConfig) {
+    println!(
+        "{}",
+        format!("Decimal mode: guesses within {} of the number will count.", format_number(config.tolerance)).yellow()
+    );
+}
+
+/// Checks whether the secret was the lucky number, announcing it if so
+fn check_lucky(secret: f64, config: &GameConfig) -> bool {
+    let lucky = is_lucky(secret, config.lucky, config.tolerance);
+    if lucky {
+        println!("{}", format!("Lucky {}! +{} points", format_number(secret), LUCKY_BONUS).magenta().bold());
+    }
+    lucky
+}
+
+/// Shows the best result so far when the round is a known challenge
+fn announce_challenge_best(scoreboard: &Scoreboard, round: &RoundState) {
+    let best = round.challenge.as_ref().and_then(|code| scoreboard.challenges.get(code));
+    if let Some(best) = best {
+        println!("Best for this challenge: {} attempts.", best.attempts);
+    }
+}
+
+/// Settings a new game starts from
+/// An adaptive or mid-session difficulty replaces the session's difficulty
+/// and its preset range, so stats are kept under the difficulty played
+/// Parameters:
+///   session_config: &GameConfig - settings the session was started with
+///   difficulty: Difficulty - difficulty picked for this game
+///   chosen: bool - whether the player changed difficulty mid-session
+fn game_settings(session_config: &GameConfig, difficulty: Difficulty, chosen: bool) -> GameConfig {
+    let mut game_config = session_config.clone();
+    if session_config.adaptive || chosen {
+        game_config.difficulty = difficulty;
+        (game_config.range_start, game_config.range_end) = difficulty.range();
+    }
+    game_config
+}
+
+/// Picks this game's band with the game's RNG, so a seed repeats the choice
+/// Parameters:
+///   config: &mut GameConfig - settings for this game; takes the band's range
+///   bands: &[GameRange] - configured bands, possibly none
+///   rng: &mut StdRng - the game's random number generator
+/// Returns:
+///   The chosen band's label (e.g. "1-1000"), or None without bands
+fn pick_band(config: &mut GameConfig, bands: &[GameRange], rng: &mut StdRng) -> Option<String> {
+    let band = bands.choose(rng)?;
+    (config.range_start, config.range_end) = (band.start, band.end);
+    if !config.explicit_mode {
+        config.mode = infer_mode(band.start, band.end);
+    }
+    Some(format!("{}-{}", band.start, band.end))
+}
+
+/// Records a win on the scoreboard, announces new records and saves
+fn record_to_scoreboard(stats: &SessionStats, scoreboard: &mut Scoreboard, config: &GameConfig, score: u32, round: &RoundState) {
+    let new = scoreboard.record_win(config.difficulty, &config.player, score, round.attempts, round.challenge.as_deref());
+    if new.best_score {
+        println!("{}", format!("New {} best score!", config.difficulty).green().bold());
+    }
+    if new.fewest_attempts {
+        println!("{}", format!("New {} fewest-guesses record!", config.difficulty).green().bold());
+    }
+    if new.challenge_best {
+        println!("{}", "New best for this challenge!".green().bold());
+    }
+    if let Some(band) = &round.band
+        && scoreboard.record_band_win(band, &config.player, score, round.attempts)
+    {
+        println!("{}", format!("New record for band {}!", band).green().bold());
+    }
+    persist(stats, || save_scores(&scoreboard_path(&config.data_dir), scoreboard));
+}
+
+/// Teases the player about their farthest-off guess of the round
+fn print_worst_guess(round: &RoundState, secret: f64, config: &GameConfig) {
+    if config.quiet {
+        return;
+    }
+    if let Some((guess, distance)) = round.worst_guess(secret) {
+        println!("Your worst guess was {}, off by {}!", format_number(guess), format_number(distance));
+    }
+}
+
+/// Compares a win with the all-time average (skipped before the first game)
+fn print_average_comparison(attempts: u32, average: Option<f64>) {
+    match average_comparison(attempts, average) {
+        Some((line, Ordering::Less)) => println!("{}", line.green()),
+        Some((line, Ordering::Greater)) => println!("{}", line.red()),
+        Some((line, Ordering::Equal)) => println!("{}", line),
+        None => {},
+    }
+}
+
+/// Words a win against the all-time average
+/// Returns:
+///   The line and how the win compares (Less = fewer attempts than usual),
+///   or None before the first game
+fn average_comparison(attempts: u32, average: Option<f64>) -> Option<(String, Ordering)> {
+    let average = average?;
+    let line = format!("You won in {} — your average is {:.1}", attempts, average);
+    Some(match (attempts as f64).partial_cmp(&average) {
+        Some(Ordering::Less) => (format!("{}, so that's better than usual!", line), Ordering::Less),
+        Some(Ordering::Greater) => (format!("{}, so that's worse than usual.", line), Ordering::Greater),
+        _ => (format!("{}, right on par.", line), Ordering::Equal),
+    })
+}
+
+/// Shows the fastest win on a difficulty, if there is one
+fn print_speedrun_pb(all_time: &PersistedStats, difficulty: Difficulty) {
+    if let Some(run) = all_time.speedruns.get(&difficulty) {
+        println!("Speedrun PB ({}): {:.1}s in {} attempts", difficulty, run.time.as_secs_f64(), run.attempts);
+    }
+}
+
+/// Records a finished game in the stats and the history log, then saves
+/// A replay of a number already won only adds its attempts and win
+/// (see `SessionStats::record_continued_round`)
+/// Parameters:
+///   stats: &mut SessionStats - session and all-time stats to update
+///   config: &GameConfig - difficulty and data directory
+///   round: &mut RoundState - the game; its unrecorded attempts are used up
+///   secret: f64 - the game's secret
+///   range: (f64, f64) - range the game was played in
+///   won: bool - whether the secret was found
+/// Returns:
+///   The game's result, or None when there was nothing new to record
+fn record_game(stats: &mut SessionStats, config: &GameConfig, round: &mut RoundState, secret: f64, range: (f64, f64), won: bool) -> Option<RoundResult> {
+    let attempts = std::mem::take(&mut round.game_attempts);
+    if attempts == 0 || (round.continued && !won) {
+        return None;
+    }
+    if round.continued {
+        stats.record_continued_round(won, attempts);
+    } else {
+        stats.record_round(won, attempts);
+        log_game(stats, config, won, attempts);
+        if let Some(band) = &round.band {
+            stats.record_band(band, won);
+        }
+        round.continued = true;
+    }
+    if won {
+        stats.all_time.record_win_on(config.difficulty);
+        stats.all_time.record_secret_win(secret, range, attempts);
+    }
+    // Save now so quitting abruptly loses at most the game in progress
+    autosave_stats(stats, config);
+    Some(RoundResult { won, attempts })
+}
+
+/// Records a number the player is moving on from (or quitting) without
+/// finding it as a lost game; a game already recorded is left alone
+fn leave_game(stats: &mut SessionStats, config: &GameConfig, round: &mut RoundState, secret: f64, range: (f64, f64)) -> Option<RoundResult> {
+    record_game(stats, config, round, secret, range, false)
+}
+
+/// Adds a finished game to the history log
+fn log_game(stats: &SessionStats, config: &GameConfig, won: bool, attempts: u32) {
+    let game = GameRecord::now(config.difficulty, won, attempts);
+    persist(stats, || append_game(&history_path(&config.data_dir), game));
+}
+
+/// Offers to reveal a secret the player has spent many tries on
+/// Returns:
+///   true if the player wants the answer
+fn prompt_reveal(total_attempts: u32) -> Result<bool, GameError> {
+    println!("\nYou've spent {} tries on this one — reveal? (y/N): ", total_attempts);
+    let answer = normalize_input(&input::read_line()?);
+    Ok(matches!(answer.to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Asks for a new guessing range and draws a secret from it
+/// Parameters:
+///   config: &GameConfig - widest span, mode and secret settings
+///   rng: &mut StdRng - the game's random number generator
+/// Returns:
+///   The new range and its secret
+fn change_range(config: &GameConfig, rng: &mut StdRng) -> Result<((f64, f64), f64), GameError> {
+    let (start, end) = game_range_adjuster(config.max_span, config.mode)?;
+    println!("New guessing range set: {:.1} to {:.1}", start, end);
+    Ok(((start, end), gen_secret(rng, start, end, config)))
+}
+
+/// Asks which difficulty to play next
+/// Parameters:
+///   current: Difficulty - difficulty being played now
+///   all_time: &PersistedStats - Hard wins, which unlock Insane
+///   unlock_wins: u32 - Hard wins needed to unlock Insane
+/// Returns:
+///   The new difficulty, or None to keep the current one (invalid, locked
+///   or unchanged choice)
+fn prompt_difficulty(current: Difficulty, all_time: &PersistedStats, unlock_wins: u32) -> Result<Option<Difficulty>, GameError> {
+    println!("\nChoose a difficulty:");
+    for (i, difficulty) in Difficulty::ALL.iter().enumerate() {
+        println!("{}. {}", i + 1, difficulty.menu_label(all_time.hard_wins, unlock_wins));
+    }
+    println!("Your choice (1-{}): ", Difficulty::ALL.len());
+    let answer = normalize_input(&input::read_line()?);
+    let choice = answer
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_sub(1))
+        .and_then(|i| Difficulty::ALL.get(i).copied())
+        .or_else(|| Difficulty::from_name(&answer));
+    match choice {
+        Some(difficulty) if difficulty.locked(all_time.hard_wins, unlock_wins) => {
+            println!("{}", format!("{} is still locked. Keeping {}.", difficulty, current).red());
+            Ok(None)
+        },
+        Some(difficulty) if difficulty != current => Ok(Some(difficulty)),
+        Some(_) => Ok(None),
+        None => {
+            println!("{}", format!("Invalid choice. Keeping {}.", current).red());
+            Ok(None)
+        },
+    }
+}
+
+/// Offers the double-or-nothing wager after a win
+/// Returns:
+///   true if the player accepts
+fn prompt_wager(score: u32) -> Result<bool, GameError> {
+    println!("\nDouble or nothing? Risk your {} points on a harder bonus round (y/N): ", score);
+    let answer = normalize_input(&input::read_line()?);
+    Ok(matches!(answer.to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Plays a bonus round with a fresh secret and a tight attempt limit
+/// Parameters:
+///   rng: game random number generator
+///   range: (f64, f64) - guessing range
+///   max_attempts: u32 - attempts allowed before the wager is lost
+///   config: &GameConfig - session settings
+/// Returns:
+///   Whether the bonus secret was found in time
+fn play_bonus_round(rng: &mut StdRng, range: (f64, f64), max_attempts: u32, config: &GameConfig) -> Result<bool, GameError> {
+    let secret_number = gen_secret(rng, range.0, range.1, config);
+    let mut round = RoundState::new(None, max_attempts);
+    println!("{}", format!("Bonus round! Find the new number in {} attempt(s).", max_attempts).purple().bold());
+
+    while round.attempts < max_attempts {
+        if game_loop(secret_number, &mut round, range, config)? {
+            return Ok(true);
+        }
+    }
+    println!("Out of attempts. The bonus number was {:.2}.", secret_number);
+    Ok(false)
+}
+
+/// Picks the session seed (from config or at random) and builds its generator
+fn session_rng(config: &GameConfig) -> (u64, StdRng) {
+    let seed = config.seed.unwrap_or_else(|| rand::rng().random());
+    println!("Session seed: {}", seed);
+    (seed, seeded_rng(seed))
+}
+
+/// Prints every guess of a round with its verdict and timing
+fn print_trace(round: &RoundState) {
+    println!("\nGuess trace:");
+    for line in trace_lines(round.trace()) {
+        println!("  {}", line);
+    }
+}
+
+/// Prints how long the player thought about each guess (not in quiet mode)
+fn print_attempt_times(round: &RoundState, config: &GameConfig) {
+    let times = attempt_times(round.trace());
+    if config.quiet || times.is_empty() {
+        return;
+    }
+    println!("\nTime per attempt:");
+    for (i, time) in times.iter().enumerate() {
+        println!("  Attempt {}: {:.1}s", i + 1, time.as_secs_f64());
+    }
+}
+
 /// Prompts player to select hint type
+/// Without menus (see `GameConfig::interactive`) no hint is taken
+/// Parameters:
+///   round: &RoundState - hints already taken this round
+///   range: (f64, f64) - current guessing range, for hint previews
+///   config: &GameConfig - per-category hint allowances
 /// Returns:
-///   String containing their choice ("1", "2", or "3")
-fn get_hint_choice() -> String {
-    println!("\nChoose a hint option:");
-    println!("1. Easy hint (simple arithmetic)");
-    println!("2. Hard hint (complex equations)");
-    println!("3. No hints (I'm feeling lucky!)");
-    println!("Your choice (1-3, default 3): ");
-    
-    let mut choice = String::new();
-    io::stdin().read_line(&mut choice).expect("Failed to read input");
-    choice
+///   The chosen hint (anything unrecognized means no hint); previews
+///   are shown here and the menu asked again
+fn get_hint_choice(round: &RoundState, range: (f64, f64), config: &GameConfig) -> Result<HintChoice, GameError> {
+    if !config.interactive() {
+        return Ok(HintChoice::NoHint);
+    }
+    let left = |category: HintCategory| match round.hints_remaining(category, config.hint_limits.get(&category).copied()) {
+        Some(left) => format!(" [{} {} left]", left, category.name()),
+        None => String::new(),
+    };
+    loop {
+        println!("\nChoose a hint option:");
+        for choice in HintChoice::MENU {
+            let left = choice.category().map(left).unwrap_or_default();
+            println!("{}{}", choice.menu_line(), left);
+        }
+        println!("Your choice (1-9, f or p, default 3): ");
+
+        match HintChoice::from_input(&normalize_input(&input::read_line()?)) {
+            HintChoice::Preview => {
+                for line in hint_previews(range, config.hint_scale) {
+                    println!("{}", line);
+                }
+            },
+            choice => return Ok(choice),
+        }
+    }
 }
 
 /// Gets player's choice after game ends
+/// Without menus a lost number is retried and a win quits
+/// Parameters:
+///   config: &GameConfig - which retry options are hidden
+///   won: bool - whether the round just ended in a win (enables replay)
 /// Returns:
-///   1 = same number, 2 = new number, 0 = quit
-fn get_retry_choice() -> i32 {
+///   1 = same number, 2 = new number, 3 = new range, 4 = reseed,
+///   5 = same number as a continuation, 6 = replay after a win, 0 = quit
+fn get_retry_choice(config: &GameConfig, won: bool) -> Result<i32, GameError> {
+    if !config.interactive() {
+        return Ok(if won { 0 } else { 1 });
+    }
+    let items = retry_menu(&config.hidden_retry, won);
     println!("\nWhat would you like to do next?");
-    println!("1. Try same number again");
-    println!("2. Get a new random number");
-    println!("3. Try again with a different guessing range");
-    println!("0. Quit game");
-    println!("Your choice (0-2): ");
+    for item in &items {
+        println!("{}. {}", item.key, item.label);
+    }
+    println!("Your choice ({}): ", choice_hint(&items));
     
-    let mut choice = String::new();
-    io::stdin().read_line(&mut choice).expect("Failed to read input");
-    choice.trim().parse().unwrap_or(0) // Default to 0 (quit) on invalid input
+    // Default to 0 (quit) on invalid input or a hidden option
+    let choice = normalize_input(&input::read_line()?);
+    Ok(choice.parse().ok().filter(|key| items.iter().any(|item| item.key == *key)).unwrap_or(0))
+}
+
+/// Lets a teacher type the secret without it showing on screen
+/// Parameters:
+///   range: (f64, f64) - the secret must fall inside this range
+///   config: &GameConfig - integer mode requires a whole number
+/// Returns:
+///   The chosen secret
+fn prompt_secret(range: (f64, f64), config: &GameConfig) -> Result<f64, GameError> {
+    loop {
+        println!("Enter the secret number ({}-{}, hidden): ", range.0, range.1);
+        let secret: f64 = match normalize_input(&input::read_hidden_line()?).parse() {
+            Ok(secret) => secret,
+            Err(_) => {
+                println!("{}", "That is not a number.".red());
+                continue;
+            }
+        };
+        if !config.game_range(range.0, range.1).contains(secret) {
+            println!("{}", format!("The secret must be in {}.", config.game_range(range.0, range.1)).red());
+        } else if config.mode == GameMode::Integer && secret.fract() != 0.0 {
+            println!("{}", "The secret must be a whole number in integer mode.".red());
+        } else {
+            return Ok(secret);
+        }
+    }
+}
+
+/// Asks for a seed until a valid whole number is entered
+/// Returns:
+///   The seed for the next number
+fn prompt_seed() -> Result<u64, GameError> {
+    loop {
+        println!("Enter a new seed (whole number): ");
+        match normalize_input(&input::read_line()?).parse() {
+            Ok(seed) => return Ok(seed),
+            Err(_) => println!("{}", "Invalid seed. Please enter a whole number.".red()),
+        }
+    }
+}
+
+/// Saves all-time stats after a round, reporting (not failing on) errors
+fn autosave_stats(stats: &SessionStats, config: &GameConfig) {
+    persist(stats, || save_stats(&stats_path(&config.data_dir), &stats.all_time));
+}
+
+/// Runs a save of stats, scores or history unless an earlier save failed
+/// The first failure warns once and turns off every later save this
+/// session, so a broken data directory doesn't repeat the same error each round
+/// Parameters:
+///   stats: &SessionStats - the session, which remembers a failed save
+///   save: the save to run
+/// Returns:
+///   The warning, when this save was the one that failed
+fn persist<E: Display>(stats: &SessionStats, save: impl FnOnce() -> Result<(), E>) -> Option<String> {
+    if stats.saves_disabled.get() {
+        return None;
+    }
+    let e = save().err()?;
+    stats.saves_disabled.set(true);
+    let warning = format!("Stats won't be saved: {}", e);
+    eprintln!("{}", warning.yellow());
+    Some(warning)
 }
 
 /// Cleanly exits the game with farewell message
-fn exit_game() {
-    println!("\nThank you for playing! Goodbye!");
+fn exit_game(stats: &SessionStats, config: &GameConfig) -> ! {
+    finish_session(stats, config);
     exit(0);
 }
+
+/// Saves all-time stats, writes the markdown and heatmap exports when
+/// requested, and says goodbye
+fn finish_session(stats: &SessionStats, config: &GameConfig) {
+    persist(stats, || save_stats(&stats_path(&config.data_dir), &stats.all_time));
+    if let Some(path) = &config.export_md {
+        match export_markdown(path, stats, &config.player) {
+            Ok(()) => println!("Stats exported to {}", path.display()),
+            Err(e) => eprintln!("{}", format!("Could not export stats: {}", e).red()),
+        }
+    }
+    if let Some(path) = &config.export_heatmap {
+        match export_heatmap(path, &stats.all_time) {
+            Ok(()) => println!("Guess heatmap exported to {}", path.display()),
+            Err(e) => eprintln!("{}", format!("Could not export heatmap: {}", e).red()),
+        }
+    }
+    for (band, tally) in &stats.bands {
+        println!("Band {}: won {} of {}", band, tally.wins, tally.games);
+    }
+    if !config.quiet {
+        print!("\n{}", personal_bests_report(&stats.all_time));
+    }
+    println!("\nThank you for playing! Goodbye!");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn win_below_the_average_is_better_than_usual() {
+        let (line, ordering) = average_comparison(5, Some(7.2)).unwrap();
+        assert_eq!(line, "You won in 5 — your average is 7.2, so that's better than usual!");
+        assert_eq!(ordering, Ordering::Less);
+    }
+
+    #[test]
+    fn win_above_the_average_is_worse_than_usual() {
+        let (line, ordering) = average_comparison(9, Some(7.2)).unwrap();
+        assert_eq!(line, "You won in 9 — your average is 7.2, so that's worse than usual.");
+        assert_eq!(ordering, Ordering::Greater);
+    }
+
+    #[test]
+    fn first_game_has_no_comparison() {
+        let stats = PersistedStats::default();
+        assert_eq!(stats.avg_attempts(), None);
+        assert_eq!(average_comparison(4, stats.avg_attempts()), None);
+    }
+
+    #[test]
+    fn finished_game_is_on_disk_before_exit() {
+        let data_dir = std::env::temp_dir().join(format!("guessing-game-autosave-{}", std::process::id()));
+        let config = GameConfig { data_dir: data_dir.clone(), ..GameConfig::default() };
+        let mut stats = SessionStats::default();
+        let mut round = RoundState::new(None, 10);
+        round.game_attempts = 3;
+
+        let result = record_game(&mut stats, &config, &mut round, 42.0, (1.0, 100.0), true);
+        // No exit_game: the process "crashes" here, so only the autosave wrote anything
+        let saved = load_stats(&stats_path(&data_dir)).unwrap();
+        let history = load_history(&history_path(&data_dir)).unwrap();
+        std::fs::remove_dir_all(&data_dir).unwrap();
+
+        assert_eq!(result, Some(RoundResult { won: true, attempts: 3 }));
+        assert_eq!((saved.games, saved.wins, saved.total_attempts), (1, 1, 3));
+        assert_eq!(history.len(), 1);
+    }
+
+    #[test]
+    fn game_is_recorded_once() {
+        let data_dir = std::env::temp_dir().join(format!("guessing-game-record-once-{}", std::process::id()));
+        let config = GameConfig { data_dir: data_dir.clone(), ..GameConfig::default() };
+        let mut stats = SessionStats::default();
+        let mut round = RoundState::new(None, 10);
+        round.game_attempts = 2;
+        record_game(&mut stats, &config, &mut round, 42.0, (1.0, 100.0), true);
+        // Moving on from a number already won records nothing more
+        assert_eq!(leave_game(&mut stats, &config, &mut round, 42.0, (1.0, 100.0)), None);
+        std::fs::remove_dir_all(&data_dir).unwrap();
+
+        assert_eq!((stats.games, stats.wins, stats.current_streak), (1, 1, 1));
+    }
+
+    #[test]
+    fn piped_range_sets_the_bounds_and_feeds_the_guesses() {
+        let mut config = GameConfig::default();
+        let piped = std::io::Cursor::new("5 250\n100\n200\r\n150\n");
+        let guesses = read_stdin_range(&mut config, piped).unwrap();
+        assert_eq!((config.range_start, config.range_end), (5.0, 250.0));
+        assert_eq!(config.mode, GameMode::Integer);
+        assert_eq!(guesses, ["100", "200", "150"]);
+
+        input::use_script(guesses);
+        let mut round = RoundState::new(None, 10);
+        let range = (config.range_start, config.range_end);
+        while !game_loop(150.0, &mut round, range, &config).unwrap() {}
+        assert_eq!(round.attempts, 3);
+    }
+
+    #[test]
+    fn malformed_range_line_is_rejected() {
+        let mut config = GameConfig::default();
+        let error = read_stdin_range(&mut config, std::io::Cursor::new("5\n100\n")).unwrap_err();
+        assert!(error.contains("expects \"START END\""), "{}", error);
+        assert!(read_stdin_range(&mut config, std::io::Cursor::new("250 5\n")).is_err());
+    }
+
+    #[test]
+    fn idle_quit_saves_the_stats() {
+        let data_dir = std::env::temp_dir().join(format!("guessing-game-idle-{}", std::process::id()));
+        let config = GameConfig { data_dir: data_dir.clone(), ..GameConfig::default() };
+        let mut stats = SessionStats::default();
+        stats.record_round(true, 4);
+
+        let code = wind_down(&GameError::from(input::InputEnd::Idle), &stats, &config);
+        let saved = load_stats(&stats_path(&data_dir)).unwrap();
+        std::fs::remove_dir_all(&data_dir).unwrap();
+
+        assert_eq!(code, 0);
+        assert_eq!((saved.games, saved.wins), (1, 1));
+    }
+
+    #[test]
+    fn unwritable_data_dir_warns_once() {
+        // A file where the data directory should be can't be written into, even as root
+        let blocker = std::env::temp_dir().join(format!("guessing-game-blocked-{}", std::process::id()));
+        std::fs::write(&blocker, "not a directory").unwrap();
+        let config = GameConfig { data_dir: blocker.join("data"), ..GameConfig::default() };
+        let stats = SessionStats::default();
+
+        let first = persist(&stats, || save_stats(&stats_path(&config.data_dir), &stats.all_time));
+        let second = persist(&stats, || save_stats(&stats_path(&config.data_dir), &stats.all_time));
+        log_game(&stats, &config, true, 3);
+        std::fs::remove_file(&blocker).unwrap();
+
+        let warning = first.expect("the first failed save warns");
+        assert!(warning.starts_with("Stats won't be saved: "), "{}", warning);
+        assert!(!warning.contains("not writable"), "{}", warning);
+        assert_eq!(second, None);
+        assert!(stats.saves_disabled.get());
+        // Another session starts with saves on
+        assert!(!SessionStats::default().saves_disabled.get());
+    }
+
+    #[test]
+    fn band_choice_repeats_under_a_seed_and_scores_are_kept_per_band() {
+        let bands = vec![
+            GameRange { start: 1.0, end: 10.0, start_inclusive: true, end_inclusive: true },
+            GameRange { start: 1.0, end: 1000.0, start_inclusive: true, end_inclusive: true },
+        ];
+        let picks = |seed| {
+            let mut rng = seeded_rng(seed);
+            (0..20).map(|_| pick_band(&mut GameConfig::default(), &bands, &mut rng).unwrap()).collect::<Vec<_>>()
+        };
+        let first = picks(7);
+        assert_eq!(first, picks(7));
+        assert!(first.contains(&String::from("1-10")) && first.contains(&String::from("1-1000")));
+
+        let mut config = GameConfig::default();
+        let label = pick_band(&mut config, &bands, &mut seeded_rng(7)).unwrap();
+        assert_eq!((config.range_start, config.range_end), if label == "1-10" { (1.0, 10.0) } else { (1.0, 1000.0) });
+
+        let mut scoreboard = Scoreboard::default();
+        let mut stats = SessionStats::default();
+        for (band, score, attempts) in [("1-10", 90, 2), ("1-1000", 60, 9), ("1-10", 70, 3)] {
+            scoreboard.record_band_win(band, "Ada", score, attempts);
+            stats.record_band(band, true);
+        }
+        stats.record_band("1-1000", false);
+        assert_eq!(scoreboard.bands["1-10"].best_score.as_ref().unwrap().score, 90);
+        assert_eq!(scoreboard.bands["1-1000"].fewest_attempts.as_ref().unwrap().attempts, 9);
+        assert_eq!((stats.bands["1-10"].wins, stats.bands["1-10"].games), (2, 2));
+        assert_eq!((stats.bands["1-1000"].wins, stats.bands["1-1000"].games), (1, 2));
+    }
+
+    #[test]
+    fn seed_variable_reproduces_the_seeded_secret() {
+        let mut config = GameConfig::default();
+        assert_eq!(seed_from_env(&mut config, Some(" 42 ")), None);
+        assert_eq!(config.seed, Some(42));
+
+        let (seed, mut rng) = session_rng(&config);
+        assert_eq!(seed, 42);
+        let secret = gen_secret(&mut rng, config.range_start, config.range_end, &config);
+        assert_eq!(secret, gen_secret(&mut seeded_rng(42), config.range_start, config.range_end, &config));
+    }
+
+    #[test]
+    fn seed_flag_beats_the_variable() {
+        let mut config = GameConfig { seed: Some(7), ..GameConfig::default() };
+        assert_eq!(seed_from_env(&mut config, Some("42")), None);
+        assert_eq!(config.seed, Some(7));
+    }
+
+    #[test]
+    fn bad_seed_variable_warns_and_stays_random() {
+        let mut config = GameConfig::default();
+        let warning = seed_from_env(&mut config, Some("lucky")).expect("a bad value warns");
+        assert!(warning.contains("GUESSINGGAME_SEED=\"lucky\""), "{}", warning);
+        assert_eq!(config.seed, None);
+
+        assert_eq!(seed_from_env(&mut config, None), None);
+        assert_eq!(config.seed, None);
+    }
+
+    #[test]
+    fn changing_difficulty_applies_its_preset_to_the_next_game() {
+        let session_config = GameConfig::default();
+        input::use_script(vec![String::from("3")]);
+        let next = prompt_difficulty(Difficulty::Medium, &PersistedStats::default(), session_config.unlock_wins).unwrap();
+        assert_eq!(next, Some(Difficulty::Hard));
+
+        let config = game_settings(&session_config, next.unwrap(), true);
+        assert_eq!(config.difficulty, Difficulty::Hard);
+        assert_eq!((config.range_start, config.range_end), Difficulty::Hard.range());
+        assert_eq!(config.attempt_ceiling, session_config.attempt_ceiling);
+
+        // Without a change the session's own settings stand
+        let unchanged = game_settings(&session_config, Difficulty::Hard, false);
+        assert_eq!((unchanged.range_start, unchanged.range_end), (session_config.range_start, session_config.range_end));
+    }
+
+    #[test]
+    fn locked_difficulty_keeps_the_current_one() {
+        input::use_script(vec![String::from("insane")]);
+        let next = prompt_difficulty(Difficulty::Hard, &PersistedStats::default(), 3).unwrap();
+        assert_eq!(next, None);
+    }
+
+    #[test]
+    fn changed_range_draws_the_next_secret_from_it() {
+        let config = GameConfig { mode: GameMode::Integer, ..GameConfig::default() };
+        input::use_script(["200", "300"].map(String::from).to_vec());
+        let (range, secret) = change_range(&config, &mut seeded_rng(9)).unwrap();
+        assert_eq!(range, (200.0, 300.0));
+        assert!((200.0..=300.0).contains(&secret), "{}", secret);
+        assert_eq!(secret, gen_secret(&mut seeded_rng(9), 200.0, 300.0, &config));
+
+        // The next round is played against that secret
+        let mut round = RoundState::new(None, config.attempt_ceiling);
+        input::use_script(vec![format_number(secret)]);
+        assert!(game_loop(secret, &mut round, range, &config).unwrap());
+    }
+}
@@ -0,0 +1,69 @@
+//! Retry menu module
+//!
+//! Builds the "what next?" menu shown after a round:
+//! - Fixed set of items, each with a number, config name and label
+//! - Items can be hidden by config (quitting always stays available)
+//! - Some items only appear after a win
+//! - The valid-choices hint is computed from the items actually shown
+
+/// One entry in the retry menu
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MenuItem {
+    /// Number the player types
+    pub key: i32,
+    /// Name used by `--hide-retry`
+    pub name: &'static str,
+    pub label: &'static str,
+    /// Only offered right after a win
+    pub after_win: bool,
+}
+
+/// Every retry option, in display order
+pub const RETRY_ITEMS: [MenuItem; 8] = [
+    MenuItem { key: 1, name: "same", label: "Try same number again", after_win: false },
+    MenuItem { key: 2, name: "new", label: "Get a new random number", after_win: false },
+    MenuItem { key: 3, name: "range", label: "Try again with a different guessing range", after_win: false },
+    MenuItem { key: 4, name: "reseed", label: "Reseed and get a new number", after_win: false },
+    MenuItem { key: 5, name: "continue", label: "Retry same number (counts as continuation)", after_win: false },
+    MenuItem { key: 6, name: "replay", label: "Replay this number to beat your attempt count", after_win: true },
+    MenuItem { key: 7, name: "difficulty", label: "Change difficulty", after_win: false },
+    MenuItem { key: 0, name: "quit", label: "Quit game", after_win: false },
+];
+
+/// Checks that a retry option can be hidden
+/// Parameters:
+///   name: &str - option name such as "range"
+/// Returns:
+///   Ok, or a message listing the names that can be hidden
+pub fn validate_hidden_item(name: &str) -> Result<(), String> {
+    if name != "quit" && RETRY_ITEMS.iter().any(|item| item.name == name) {
+        return Ok(());
+    }
+    let names: Vec<&str> = RETRY_ITEMS.iter().map(|item| item.name).filter(|&name| name != "quit").collect();
+    Err(format!("Unknown retry option '{}' (expected one of: {})", name, names.join(", ")))
+}
+
+/// The retry menu with hidden options left out
+/// Parameters:
+///   hidden: &[String] - names of options to leave out
+///   won: bool - whether the round just ended in a win
+pub fn retry_menu(hidden: &[String], won: bool) -> Vec<MenuItem> {
+    RETRY_ITEMS
+        .iter()
+        .filter(|item| won || !item.after_win)
+        .filter(|item| !hidden.iter().any(|name| name == item.name))
+        .copied()
+        .collect()
+}
+
+/// Describes which numbers are valid, e.g. "0-4" or "0, 1, 2, 4"
+pub fn choice_hint(items: &[MenuItem]) -> String {
+    let mut keys: Vec<i32> = items.iter().map(|item| item.key).collect();
+    keys.sort_unstable();
+    match (keys.first(), keys.last()) {
+        (Some(first), Some(last)) if (last - first) as usize + 1 == keys.len() => {
+            if first == last { first.to_string() } else { format!("{}-{}", first, last) }
+        },
+        _ => keys.iter().map(|key| key.to_string()).collect::<Vec<_>>().join(", "),
+    }
+}
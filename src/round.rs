@@ -0,0 +1,798 @@
+//! Round state module
+//!
+//! Tracks everything that belongs to the secret currently being guessed:
+//! - Attempt count and round timer
+//! - Hints used against the budget
+//! - Personal attempt target
+//! - Streaks of far-off guesses (for the mercy hint)
+//! - Guess trace (value, verdict, closeness, timing) and a recap of the search style
+//! - Scoring for a finished round
+//! - Adaptive difficulty from recent results
+//! - Escalating ranges that tighten after each win
+
+use std::time::{Duration, Instant};
+use std::collections::HashMap;
+use crate::config::{Difficulty, GameMode, HintCategory};
+use crate::utils::{HintDeck, format_number};
+
+/// Points for a first-try win; each extra attempt costs `ATTEMPT_PENALTY`
+const BASE_SCORE: u32 = 100;
+const ATTEMPT_PENALTY: u32 = 10;
+/// Every win is worth at least this much
+const MIN_SCORE: u32 = 10;
+/// Extra points when the secret was the lucky number
+pub const LUCKY_BONUS: u32 = 50;
+/// Points lost for each hint taken
+const HINT_PENALTY: u32 = 5;
+/// Extra points for winning within the personal target
+pub const GOAL_BONUS: u32 = 25;
+
+/// Guesses below this closeness percentage count as "far off"
+const FAR_CLOSENESS_PCT: f64 = 10.0;
+/// Consecutive far-off guesses before the mercy hint is offered
+const MERCY_STREAK: u32 = 3;
+
+/// A guess that leaves more than this fraction of the known interval was a weak cut
+const EFFICIENT_CUT: f64 = 0.75;
+
+/// How the player's guesses homed in on the secret
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchStyle {
+    /// Every guess cut the known interval roughly in half
+    Bisecting,
+    /// Every guess stayed inside the known interval, but some cuts were small
+    Narrowing,
+    /// Some guesses fell outside what was already known
+    Scattered,
+}
+
+impl SearchStyle {
+    /// One-line recap for the end of a round
+    pub fn describe(&self) -> &'static str {
+        match self {
+            SearchStyle::Bisecting => "You bisected efficiently (avg step halved each time).",
+            SearchStyle::Narrowing => "You closed in steadily, but bolder cuts would get there faster.",
+            SearchStyle::Scattered => "Your guesses jumped around.",
+        }
+    }
+}
+
+/// How a guess compared to the secret
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verdict {
+    TooSmall,
+    TooBig,
+    Correct,
+}
+
+impl Verdict {
+    /// Lowercase wording, e.g. "too big"
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Verdict::TooSmall => "too small",
+            Verdict::TooBig => "too big",
+            Verdict::Correct => "correct",
+        }
+    }
+}
+
+/// One valid guess, as it happened
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GuessEvent {
+    pub value: f64,
+    pub verdict: Verdict,
+    /// How close the guess was (0-100)
+    pub closeness: f64,
+    /// Time since the round started
+    pub at: Duration,
+}
+
+/// State of the round in progress
+pub struct RoundState {
+    pub attempts: u32,
+    /// Attempts on this secret across same-number retries (never reset by them)
+    pub total_attempts: u32,
+    /// Attempts since this game was last recorded in the stats
+    pub game_attempts: u32,
+    /// Whether the reveal offer was already made for this secret
+    pub reveal_offered: bool,
+    /// When the round began, for time limits
+    pub started: Instant,
+    /// Set when the time limit ran out
+    pub timed_out: bool,
+    /// Points lost to wrong guesses (see `GameConfig::point_budget`)
+    pub points_spent: u32,
+    /// Set when wrong guesses used up the whole point budget
+    pub out_of_points: bool,
+    /// Hints taken this round
+    pub hints_used: u32,
+    /// Hints taken this round, per category
+    pub hints_by_category: HashMap<HintCategory, u32>,
+    /// Hints still allowed per limited category
+    pub hints_left: HashMap<HintCategory, u32>,
+    /// Shuffled hint order, when hints are drawn without replacement
+    pub hint_deck: Option<HintDeck>,
+    /// Personal goal: win in this many attempts or fewer
+    pub target: Option<u32>,
+    /// Consecutive far-off guesses without improvement
+    pub far_streak: u32,
+    /// Closeness of the previous guess, to spot improvement
+    pub last_closeness: Option<f64>,
+    /// Every valid guess, in order
+    trace: Vec<GuessEvent>,
+    /// Already recorded in the stats (after a win); replays only add to it
+    pub continued: bool,
+    /// Challenge code when the secret came straight from a known seed
+    pub challenge: Option<String>,
+    /// Fewest attempts in any win on this secret, across replays
+    pub best_run: Option<u32>,
+    /// Label of the range band this secret was drawn from, if bands are in use
+    pub band: Option<String>,
+    /// Highest guess known to be too small
+    pub known_low: Option<f64>,
+    /// Lowest guess known to be too big
+    pub known_high: Option<f64>,
+    /// Guess nearest the secret so far
+    pub closest: Option<f64>,
+}
+
+/// Most guesses a new round's trace makes room for up front
+const TRACE_RESERVE_LIMIT: u32 = 256;
+
+impl RoundState {
+    /// Starts a fresh round with no attempts made
+    /// Parameters:
+    ///   target: Option<u32> - personal goal, in attempts
+    ///   max_attempts: u32 - attempts the round may take; the trace reserves
+    ///     room for that many (up to `TRACE_RESERVE_LIMIT`)
+    pub fn new(target: Option<u32>, max_attempts: u32) -> Self {
+        RoundState {
+            attempts: 0,
+            total_attempts: 0,
+            game_attempts: 0,
+            reveal_offered: false,
+            started: Instant::now(),
+            timed_out: false,
+            points_spent: 0,
+            out_of_points: false,
+            hints_used: 0,
+            hints_by_category: HashMap::new(),
+            hints_left: HashMap::new(),
+            hint_deck: None,
+            target,
+            far_streak: 0,
+            last_closeness: None,
+            trace: Vec::with_capacity(max_attempts.min(TRACE_RESERVE_LIMIT) as usize),
+            known_low: None,
+            known_high: None,
+            closest: None,
+            continued: false,
+            challenge: None,
+            best_run: None,
+            band: None,
+        }
+    }
+
+    /// Hints still allowed in a category this round
+    /// Parameters:
+    ///   category: HintCategory - category to check
+    ///   limit: Option<u32> - the category's per-round allowance, if limited
+    /// Returns:
+    ///   None when the category is unlimited
+    pub fn hints_remaining(&self, category: HintCategory, limit: Option<u32>) -> Option<u32> {
+        limit.map(|limit| self.hints_left.get(&category).copied().unwrap_or(limit))
+    }
+
+    /// Charges a wrong guess against the point budget
+    /// Parameters:
+    ///   closeness: f64 - how close the guess was (0-100)
+    ///   budget: u32 - points the round started with
+    /// Returns:
+    ///   The cost and the points left; none left marks the round out of points
+    pub fn spend_points(&mut self, closeness: f64, budget: u32) -> (u32, u32) {
+        let cost = mistake_cost(closeness);
+        self.points_spent = self.points_spent.saturating_add(cost);
+        let left = budget.saturating_sub(self.points_spent);
+        self.out_of_points = left == 0;
+        (cost, left)
+    }
+
+    /// Total hints taken this round, across categories
+    pub fn hint_count(&self) -> u32 {
+        self.hints_by_category.values().sum()
+    }
+
+    /// One-line account of the hints taken this round
+    /// e.g. "You used 2 easy and 1 hard hint this round."
+    /// The noun agrees with the count right before it, as it reads
+    pub fn hint_summary(&self) -> String {
+        let counts: Vec<(u32, &str)> = HintCategory::ALL
+            .iter()
+            .filter_map(|category| {
+                let count = self.hints_by_category.get(category).copied().unwrap_or(0);
+                (count > 0).then(|| (count, category.name()))
+            })
+            .collect();
+        let parts: Vec<String> = counts.iter().map(|(count, name)| format!("{} {}", count, name)).collect();
+        let listed = match parts.split_last() {
+            None => return String::from("No hints used — nice!"),
+            Some((last, [])) => last.clone(),
+            Some((last, rest)) => format!("{} and {}", rest.join(", "), last),
+        };
+        let noun = if counts.last().is_some_and(|&(count, _)| count == 1) { "hint" } else { "hints" };
+        format!("You used {} {} this round.", listed, noun)
+    }
+
+    /// Adds a guess to the round's trace, stamped with the time since the start
+    pub fn record_guess(&mut self, value: f64, verdict: Verdict, closeness: f64) {
+        let at = self.started.elapsed();
+        self.trace.push(GuessEvent { value, verdict, closeness, at });
+    }
+
+    /// Every valid guess so far, in order
+    pub fn trace(&self) -> &[GuessEvent] {
+        &self.trace
+    }
+
+    /// Whether a guess is closer to the secret than the previous one
+    /// None for the round's first guess
+    pub fn trend(&self, guess: f64, secret: f64) -> Option<&'static str> {
+        let previous = (self.trace.last()?.value - secret).abs();
+        let distance = (guess - secret).abs();
+        Some(if distance < previous {
+            "Warmer"
+        } else if distance > previous {
+            "Colder"
+        } else {
+            "Same distance"
+        })
+    }
+
+    /// An earlier guess of exactly this value this round, if any
+    pub fn repeat_of(&self, guess: f64) -> Option<&GuessEvent> {
+        self.trace.iter().find(|event| event.value == guess)
+    }
+
+    /// The guess farthest from the secret, with its distance
+    /// None until there are at least two guesses to compare
+    pub fn worst_guess(&self, secret: f64) -> Option<(f64, f64)> {
+        if self.trace.len() < 2 {
+            return None;
+        }
+        self.trace
+            .iter()
+            .map(|event| (event.value, (event.value - secret).abs()))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Just the guessed values, in order
+    pub fn guesses(&self) -> Vec<f64> {
+        self.trace.iter().map(|event| event.value).collect()
+    }
+
+    /// Records what a wrong guess revealed about where the secret is
+    /// Parameters:
+    ///   guess: f64 - the wrong guess
+    ///   too_small: bool - whether the guess was below the secret
+    pub fn narrow_bounds(&mut self, guess: f64, too_small: bool) {
+        if too_small {
+            self.known_low = Some(self.known_low.map_or(guess, |low| low.max(guess)));
+        } else {
+            self.known_high = Some(self.known_high.map_or(guess, |high| high.min(guess)));
+        }
+    }
+
+    /// Advice for a guess that earlier guesses already ruled out
+    /// Returns:
+    ///   The nudge to show, or None when the guess is still possible
+    pub fn bounds_nudge(&self, guess: f64) -> Option<String> {
+        if let Some(low) = self.known_low.filter(|&low| guess <= low) {
+            return Some(format!("You already know it's above {} — that guess can't be right.", format_number(low)));
+        }
+        self.known_high
+            .filter(|&high| guess >= high)
+            .map(|high| format!("You already know it's below {} — that guess can't be right.", format_number(high)))
+    }
+
+    /// Interval the player has narrowed the secret to
+    /// Parameters:
+    ///   range: (f64, f64) - full guessing range, used where nothing is known yet
+    pub fn known_bounds(&self, range: (f64, f64)) -> (f64, f64) {
+        (self.known_low.unwrap_or(range.0), self.known_high.unwrap_or(range.1))
+    }
+
+    /// Ranks this round's guesses by how much each shrank the interval
+    /// the player knew the secret was in (most useful first)
+    /// Only the too-big/too-small answers are used, never the secret
+    /// Parameters:
+    ///   range: (f64, f64) - full guessing range the round started with
+    /// Returns:
+    ///   (guess, width it cut off) pairs
+    pub fn guess_usefulness(&self, range: (f64, f64)) -> Vec<(f64, f64)> {
+        let (mut low, mut high) = range;
+        let mut ranked: Vec<(f64, f64)> = self
+            .trace
+            .iter()
+            .map(|event| {
+                let before = high - low;
+                match event.verdict {
+                    Verdict::TooSmall => low = low.max(event.value),
+                    Verdict::TooBig => high = high.min(event.value),
+                    Verdict::Correct => (low, high) = (event.value, event.value),
+                }
+                (event.value, before - (high - low).max(0.0))
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked
+    }
+
+    /// Keeps track of the guess nearest the secret
+    /// Returns:
+    ///   (closest guess, its distance from the secret)
+    pub fn track_closest(&mut self, guess: f64, secret: f64) -> (f64, f64) {
+        let closest = match self.closest {
+            Some(best) if (best - secret).abs() <= (guess - secret).abs() => best,
+            _ => guess,
+        };
+        self.closest = Some(closest);
+        (closest, (closest - secret).abs())
+    }
+
+    /// Updates the far-off streak with a new wrong guess
+    /// Parameters:
+    ///   closeness_pct: f64 - how close the guess was (0-100)
+    /// Returns:
+    ///   true when this guess completes a streak worth a mercy hint
+    pub fn track_far_guess(&mut self, closeness_pct: f64) -> bool {
+        let improved = self.last_closeness.is_some_and(|last| closeness_pct > last);
+        self.last_closeness = Some(closeness_pct);
+
+        if closeness_pct < FAR_CLOSENESS_PCT && !improved {
+            self.far_streak += 1;
+        } else {
+            self.far_streak = 0;
+        }
+
+        if self.far_streak >= MERCY_STREAK {
+            self.far_streak = 0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Starts another try at the same secret
+    /// Parameters:
+    ///   carry: bool - keep counting attempts rather than starting over from 0
+    pub fn retry_same_number(&mut self, carry: bool) {
+        if !carry {
+            self.attempts = 0;
+        }
+    }
+
+    /// Whether the attempt count so far meets the personal target
+    /// Returns:
+    ///   None when no target is set
+    pub fn goal_met(&self) -> Option<bool> {
+        self.target.map(|target| self.attempts <= target)
+    }
+}
+
+/// Scores a winning round
+/// Parameters:
+///   attempts: u32 - attempts taken to win
+///   goal_met: bool - whether the personal target was met
+///   hints: u32 - hints taken during the round
+///   lucky: bool - whether the secret was the lucky number
+/// Returns:
+///   Points earned for the round
+pub fn compute_score(attempts: u32, goal_met: bool, hints: u32, lucky: bool) -> u32 {
+    let penalty = (attempts.max(1) - 1)
+        .saturating_mul(ATTEMPT_PENALTY)
+        .saturating_add(hints.saturating_mul(HINT_PENALTY));
+    let mut score = BASE_SCORE.saturating_sub(penalty).max(MIN_SCORE);
+    if goal_met {
+        score += GOAL_BONUS;
+    }
+    if lucky {
+        score += LUCKY_BONUS;
+    }
+    score
+}
+
+/// Whether the secret counts as the lucky number
+/// Decimal secrets are never exact, so anything a winning guess could be
+/// (within the tolerance) of the lucky number counts
+/// Parameters:
+///   secret: f64 - the secret number
+///   lucky: Option<f64> - the configured lucky number, if any
+///   tolerance: f64 - how far off a guess may be and still win
+pub fn is_lucky(secret: f64, lucky: Option<f64>, tolerance: f64) -> bool {
+    lucky.is_some_and(|lucky| (secret - lucky).abs() <= tolerance)
+}
+
+/// Builds a stable, grep-friendly summary of a finished round
+/// Parameters:
+///   won: bool - round outcome
+///   attempts: u32 - attempts used
+///   seed: u64 - seed that produced the secret
+///   range: (f64, f64) - guessing range
+///   score: u32 - points earned (0 for a loss)
+/// Returns:
+///   A single uncolored line of key=value pairs
+pub fn result_line(won: bool, attempts: u32, seed: u64, range: (f64, f64), score: u32) -> String {
+    format!(
+        "RESULT won={} attempts={} seed={} range={}..{} score={}",
+        won, attempts, seed, range.0, range.1, score
+    )
+}
+
+/// Renders a round's trace, one line per guess
+pub fn trace_lines(trace: &[GuessEvent]) -> Vec<String> {
+    trace
+        .iter()
+        .enumerate()
+        .map(|(i, event)| {
+            format!(
+                "#{} {} {} ({:.0}% close) at {:.1}s",
+                i + 1,
+                event.value,
+                event.verdict.describe(),
+                event.closeness,
+                event.at.as_secs_f64()
+            )
+        })
+        .collect()
+}
+
+/// Thinking time spent on each guess
+/// The first guess is timed from the start of the round, later ones from
+/// the guess before
+pub fn attempt_times(trace: &[GuessEvent]) -> Vec<Duration> {
+    let mut previous = Duration::ZERO;
+    trace
+        .iter()
+        .map(|event| {
+            let spent = event.at.saturating_sub(previous);
+            previous = event.at;
+            spent
+        })
+        .collect()
+}
+
+/// Classifies a round's guesses by how well each narrowed the known interval
+/// Parameters:
+///   history: &[f64] - guesses in order
+///   secret: f64 - target number
+///   start: f64 - range lower bound
+///   end: f64 - range upper bound
+/// Returns:
+///   The overall search style
+pub fn analyze_search(history: &[f64], secret: f64, start: f64, end: f64) -> SearchStyle {
+    let (mut low, mut high) = (start, end);
+    let mut all_efficient = true;
+
+    for &guess in history {
+        if guess < low || guess > high {
+            return SearchStyle::Scattered;
+        }
+        let width = high - low;
+        if guess < secret {
+            low = guess;
+        } else if guess > secret {
+            high = guess;
+        } else {
+            low = guess;
+            high = guess;
+        }
+        if width > 0.0 && (high - low) > width * EFFICIENT_CUT {
+            all_efficient = false;
+        }
+    }
+
+    if all_efficient { SearchStyle::Bisecting } else { SearchStyle::Narrowing }
+}
+
+/// Points a wrong guess costs under a point budget: 1 for a near miss,
+/// up to 10 for a guess at the far end of the range
+/// Parameters:
+///   closeness: f64 - how close the guess was (0-100)
+pub fn mistake_cost(closeness: f64) -> u32 {
+    ((100.0 - closeness) / 10.0).ceil().clamp(1.0, 10.0) as u32
+}
+
+/// Number of recent games the adaptive difficulty looks at
+const ADAPTIVE_WINDOW: usize = 2;
+
+/// Outcome of one finished game
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoundResult {
+    pub won: bool,
+    pub attempts: u32,
+}
+
+/// Picks the next difficulty from a sliding window of recent results
+/// Two quick wins raise it, two losses lower it; otherwise it stays
+/// Parameters:
+///   current: Difficulty - difficulty just played
+///   recent: &[RoundResult] - results so far, oldest first
+/// Returns:
+///   The difficulty for the next game (clamped at Easy/Insane)
+pub fn next_difficulty(current: Difficulty, recent: &[RoundResult]) -> Difficulty {
+    if recent.len() < ADAPTIVE_WINDOW {
+        return current;
+    }
+    let window = &recent[recent.len() - ADAPTIVE_WINDOW..];
+    let (start, end) = current.range();
+    let quick = (end - start).log2().ceil() as u32;
+
+    if window.iter().all(|result| result.won && result.attempts <= quick) {
+        current.harder()
+    } else if window.iter().all(|result| !result.won) {
+        current.easier()
+    } else {
+        current
+    }
+}
+
+/// Smallest span an escalating range tightens to
+pub const MIN_ESCALATING_SPAN: f64 = 10.0;
+
+/// Tightens an escalating range after a win
+/// The span halves (never below `MIN_ESCALATING_SPAN`) and the new range
+/// stays inside the old one; whole-number games keep whole bounds
+/// Parameters:
+///   range: (f64, f64) - range of the game just won
+///   position: f64 - where the new range sits inside the old one (0 = low end, 1 = high end)
+///   mode: GameMode - whether bounds must be whole numbers
+/// Returns:
+///   The range for the next game (unchanged once at the minimum span)
+pub fn tighten_range(range: (f64, f64), position: f64, mode: GameMode) -> (f64, f64) {
+    let span = range.1 - range.0;
+    let mut new_span = (span / 2.0).max(MIN_ESCALATING_SPAN);
+    if mode == GameMode::Integer {
+        new_span = new_span.ceil();
+    }
+    if new_span >= span {
+        return range;
+    }
+    let mut start = range.0 + (span - new_span) * position.clamp(0.0, 1.0);
+    if mode == GameMode::Integer {
+        start = start.floor();
+    }
+    (start, start + new_span)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn won(attempts: u32) -> RoundResult {
+        RoundResult { won: true, attempts }
+    }
+
+    fn lost(attempts: u32) -> RoundResult {
+        RoundResult { won: false, attempts }
+    }
+
+    #[test]
+    fn two_quick_wins_raise_the_difficulty() {
+        assert_eq!(next_difficulty(Difficulty::Medium, &[won(3), won(5)]), Difficulty::Hard);
+    }
+
+    #[test]
+    fn two_losses_lower_the_difficulty() {
+        assert_eq!(next_difficulty(Difficulty::Hard, &[won(2), lost(12), lost(9)]), Difficulty::Medium);
+    }
+
+    #[test]
+    fn slow_wins_and_mixed_results_keep_the_difficulty() {
+        assert_eq!(next_difficulty(Difficulty::Medium, &[won(3), won(20)]), Difficulty::Medium);
+        assert_eq!(next_difficulty(Difficulty::Medium, &[lost(4), won(3)]), Difficulty::Medium);
+        assert_eq!(next_difficulty(Difficulty::Medium, &[won(1)]), Difficulty::Medium);
+    }
+
+    #[test]
+    fn difficulty_clamps_at_the_extremes() {
+        assert_eq!(next_difficulty(Difficulty::Insane, &[won(1), won(1)]), Difficulty::Insane);
+        assert_eq!(next_difficulty(Difficulty::Easy, &[lost(9), lost(9)]), Difficulty::Easy);
+    }
+
+    #[test]
+    fn trace_reserves_room_for_the_attempts_allowed() {
+        assert!(RoundState::new(None, 12).trace.capacity() >= 12);
+        assert!(RoundState::new(None, 1_000_000).trace.capacity() < 1_000_000);
+    }
+
+    #[test]
+    fn trace_lines_walk_the_round_in_order() {
+        let mut round = RoundState::new(None, 3);
+        round.record_guess(50.0, Verdict::TooBig, 70.0);
+        round.record_guess(25.0, Verdict::TooSmall, 95.0);
+        round.record_guess(30.0, Verdict::Correct, 100.0);
+        let values: Vec<f64> = round.trace().iter().map(|event| event.value).collect();
+        assert_eq!(values, [50.0, 25.0, 30.0]);
+
+        let lines = trace_lines(round.trace());
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with(&format!("#1 50 {} (70% close) at ", Verdict::TooBig.describe())));
+        assert!(lines[1].starts_with(&format!("#2 25 {} (95% close) at ", Verdict::TooSmall.describe())));
+        assert!(lines[2].starts_with(&format!("#3 30 {} (100% close) at ", Verdict::Correct.describe())));
+        assert!(lines.iter().all(|line| line.ends_with('s')));
+    }
+
+    #[test]
+    fn escalating_range_tightens_on_each_win() {
+        let first = (1.0, 100.0);
+        let second = tighten_range(first, 0.5, GameMode::Integer);
+        let third = tighten_range(second, 0.25, GameMode::Integer);
+        let span = |range: (f64, f64)| range.1 - range.0;
+        assert!(span(second) < span(first) && span(third) < span(second));
+        assert!(span(third) >= MIN_ESCALATING_SPAN);
+        assert!(first.0 <= third.0 && third.1 <= first.1);
+        assert_eq!((third.0.fract(), third.1.fract()), (0.0, 0.0));
+    }
+
+    #[test]
+    fn escalating_range_stops_at_the_minimum_span() {
+        let mut range = (0.0, 1000.0);
+        for _ in 0..20 {
+            range = tighten_range(range, 0.9, GameMode::Decimal);
+        }
+        assert_eq!(range.1 - range.0, MIN_ESCALATING_SPAN);
+        assert_eq!(tighten_range(range, 0.9, GameMode::Decimal), range);
+    }
+
+    #[test]
+    fn far_misses_cost_more_points() {
+        assert!(mistake_cost(5.0) > mistake_cost(95.0));
+        assert_eq!(mistake_cost(95.0), 1);
+        assert_eq!(mistake_cost(0.0), 10);
+    }
+
+    #[test]
+    fn spending_the_whole_budget_ends_the_round() {
+        let mut round = RoundState::new(None, 10);
+        assert_eq!(round.spend_points(50.0, 12), (5, 7));
+        assert!(!round.out_of_points);
+        assert_eq!(round.spend_points(0.0, 12), (10, 0));
+        assert!(round.out_of_points);
+    }
+
+    #[test]
+    fn lucky_win_earns_the_bonus() {
+        let normal = compute_score(3, false, 0, false);
+        assert_eq!(normal, 80);
+        assert_eq!(compute_score(3, false, 0, true), normal + LUCKY_BONUS);
+    }
+
+    #[test]
+    fn lucky_number_matches_within_the_tolerance() {
+        assert!(is_lucky(7.0, Some(7.0), 0.0));
+        assert!(!is_lucky(8.0, Some(7.0), 0.0));
+        assert!(!is_lucky(7.0, None, 0.0));
+        // A decimal secret near the lucky number still counts
+        assert!(is_lucky(42.318, Some(42.0), 0.99));
+        assert!(!is_lucky(43.5, Some(42.0), 0.99));
+    }
+
+    #[test]
+    fn attempt_times_are_the_gaps_between_guesses() {
+        let event = |secs: f64| GuessEvent { value: 1.0, verdict: Verdict::TooSmall, closeness: 0.0, at: Duration::from_secs_f64(secs) };
+        let trace = [event(2.5), event(14.9), event(15.4)];
+        let times: Vec<f64> = attempt_times(&trace).iter().map(Duration::as_secs_f64).collect();
+        assert_eq!(times.len(), 3);
+        // The first guess is timed from the start of the round
+        assert!((times[0] - 2.5).abs() < 1e-9);
+        assert!((times[1] - 12.4).abs() < 1e-9);
+        assert!((times[2] - 0.5).abs() < 1e-9);
+        assert!(attempt_times(&[]).is_empty());
+    }
+
+    #[test]
+    fn five_attempt_win_meets_six_and_misses_four() {
+        let score = |target| {
+            let mut round = RoundState::new(target, 0);
+            round.attempts = 5;
+            let met = round.goal_met();
+            (met, compute_score(round.attempts, met == Some(true), 0, false))
+        };
+        let (plain_met, plain) = score(None);
+        assert_eq!(plain_met, None);
+        assert_eq!(score(Some(6)), (Some(true), plain + GOAL_BONUS));
+        assert_eq!(score(Some(5)), (Some(true), plain + GOAL_BONUS));
+        assert_eq!(score(Some(4)), (Some(false), plain));
+    }
+
+    #[test]
+    fn third_far_guess_in_a_row_earns_mercy() {
+        let mut round = RoundState::new(None, 0);
+        let closeness = |guess| crate::utils::closeness_pct(guess, 100.0, (1.0, 100.0));
+        assert!(!round.track_far_guess(closeness(3.0)));
+        assert!(!round.track_far_guess(closeness(2.0)));
+        assert!(round.track_far_guess(closeness(1.0)));
+        assert!(crate::utils::mercy_hint(100.0, (1.0, 100.0)).contains("upper half"));
+    }
+
+    #[test]
+    fn improving_guess_resets_the_far_streak() {
+        let mut round = RoundState::new(None, 0);
+        assert!(!round.track_far_guess(2.0));
+        assert!(!round.track_far_guess(1.0));
+        assert!(!round.track_far_guess(5.0));
+        assert!(!round.track_far_guess(4.0));
+        assert!(!round.track_far_guess(3.0));
+        assert!(round.track_far_guess(2.0));
+    }
+
+    #[test]
+    fn closest_guess_so_far_is_tracked() {
+        let mut round = RoundState::new(None, 0);
+        assert_eq!(round.track_closest(40.0, 58.0), (40.0, 18.0));
+        assert_eq!(round.track_closest(70.0, 58.0), (70.0, 12.0));
+        assert_eq!(round.track_closest(55.0, 58.0), (55.0, 3.0));
+        assert_eq!(round.track_closest(90.0, 58.0), (55.0, 3.0));
+    }
+
+    #[test]
+    fn worst_guess_is_the_farthest_from_the_secret() {
+        let mut round = RoundState::new(None, 0);
+        round.record_guess(50.0, Verdict::TooSmall, 86.0);
+        assert_eq!(round.worst_guess(64.0), None);
+
+        round.record_guess(3.0, Verdict::TooSmall, 38.0);
+        round.record_guess(80.0, Verdict::TooBig, 84.0);
+        round.record_guess(64.0, Verdict::Correct, 100.0);
+        assert_eq!(round.worst_guess(64.0), Some((3.0, 61.0)));
+    }
+
+    #[test]
+    fn binary_search_is_bisecting() {
+        let history = [50.0, 75.0, 62.0, 68.0, 65.0, 66.0];
+        assert_eq!(analyze_search(&history, 66.0, 1.0, 100.0), SearchStyle::Bisecting);
+    }
+
+    #[test]
+    fn small_steps_are_narrowing() {
+        let history = [10.0, 20.0, 30.0, 40.0, 50.0, 60.0, 66.0];
+        assert_eq!(analyze_search(&history, 66.0, 1.0, 100.0), SearchStyle::Narrowing);
+    }
+
+    #[test]
+    fn guesses_outside_the_known_interval_are_scattered() {
+        let history = [50.0, 90.0, 20.0, 66.0];
+        assert_eq!(analyze_search(&history, 66.0, 1.0, 100.0), SearchStyle::Scattered);
+    }
+
+    #[test]
+    fn guesses_rank_by_how_much_they_narrowed() {
+        let mut round = RoundState::new(None, 0);
+        round.record_guess(50.0, Verdict::TooSmall, 87.0);
+        round.record_guess(90.0, Verdict::TooBig, 73.0);
+        round.record_guess(60.0, Verdict::TooSmall, 97.0);
+        round.record_guess(70.0, Verdict::TooBig, 93.0);
+        assert_eq!(
+            round.guess_usefulness((1.0, 100.0)),
+            [(50.0, 49.0), (70.0, 20.0), (90.0, 10.0), (60.0, 10.0)]
+        );
+    }
+
+    #[test]
+    fn same_number_retry_carries_or_resets_attempts() {
+        for (carry, expected) in [(true, 6), (false, 2)] {
+            let mut round = RoundState::new(None, 0);
+            round.attempts = 4;
+            round.total_attempts = 4;
+            round.retry_same_number(carry);
+            crate::input::use_script(["10", "20"].map(String::from).to_vec());
+            let config = crate::config::GameConfig::default();
+            for _ in 0..2 {
+                assert!(!crate::utils::game_loop(50.0, &mut round, (1.0, 100.0), &config).unwrap());
+            }
+            assert_eq!(round.attempts, expected, "carry {}", carry);
+            assert_eq!(round.total_attempts, 6);
+        }
+        assert!(crate::config::parse_args([String::from("--reset-attempts")]).is_ok_and(|config| !config.carry_attempts));
+    }
+}
@@ -0,0 +1,337 @@
+//! Scoreboard module
+//!
+//! Keeps the best results per difficulty on disk:
+//! - Highest-scoring win
+//! - Fewest-guesses win (hints lower score but not attempts)
+//! - Fewest-guesses win per challenge (same seed and range)
+//! - Both records per range band, for sessions that mix bands
+//! - Loading (including older single-record files) and saving as JSON
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use crate::config::{Difficulty, GameMode};
+use crate::json::{self, Json};
+
+/// Current save-file layout version
+const SCOREBOARD_VERSION: u64 = 2;
+/// Scoreboard file name inside the data directory
+pub const SCOREBOARD_FILE: &str = "scoreboard.json";
+
+/// One recorded win
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreEntry {
+    pub player: String,
+    pub score: u32,
+    pub attempts: u32,
+}
+
+/// Best results for one difficulty
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DifficultyRecords {
+    pub best_score: Option<ScoreEntry>,
+    pub fewest_attempts: Option<ScoreEntry>,
+}
+
+impl DifficultyRecords {
+    /// Updates each record independently with a win
+    /// Returns:
+    ///   (new best score, new fewest attempts)
+    fn update(&mut self, entry: &ScoreEntry) -> (bool, bool) {
+        let best_score = self.best_score.as_ref().is_none_or(|best| entry.score > best.score);
+        if best_score {
+            self.best_score = Some(entry.clone());
+        }
+        let fewest_attempts = self.fewest_attempts.as_ref().is_none_or(|best| entry.attempts < best.attempts);
+        if fewest_attempts {
+            self.fewest_attempts = Some(entry.clone());
+        }
+        (best_score, fewest_attempts)
+    }
+}
+
+/// Which records a win just set
+#[derive(Debug, Default, PartialEq)]
+pub struct NewRecords {
+    pub best_score: bool,
+    pub fewest_attempts: bool,
+    pub challenge_best: bool,
+}
+
+/// All records, keyed by difficulty
+#[derive(Debug, Default)]
+pub struct Scoreboard {
+    pub records: BTreeMap<Difficulty, DifficultyRecords>,
+    /// Fewest-guesses win per challenge code
+    pub challenges: BTreeMap<String, ScoreEntry>,
+    /// Records per range band, keyed by the band's label (e.g. "1-1000")
+    pub bands: BTreeMap<String, DifficultyRecords>,
+}
+
+/// Identifies a shareable challenge: the same seed, range and mode
+/// always produce the same first secret
+pub fn challenge_code(seed: u64, range: (f64, f64), mode: GameMode) -> String {
+    let mode = match mode {
+        GameMode::Integer => "int",
+        GameMode::Decimal => "dec",
+    };
+    format!("{}:{}-{}:{}", seed, range.0, range.1, mode)
+}
+
+impl Scoreboard {
+    /// Records a win, updating each record independently
+    /// Parameters:
+    ///   difficulty: Difficulty - difficulty the round was played on
+    ///   player: &str - who won
+    ///   score: u32 - points earned
+    ///   attempts: u32 - attempts taken
+    ///   challenge: Option<&str> - challenge code, when the secret came from a known seed
+    /// Returns:
+    ///   Which records were beaten
+    pub fn record_win(&mut self, difficulty: Difficulty, player: &str, score: u32, attempts: u32, challenge: Option<&str>) -> NewRecords {
+        let entry = ScoreEntry { player: player.to_string(), score, attempts };
+        let mut new = NewRecords::default();
+
+        if let Some(code) = challenge
+            && self.challenges.get(code).is_none_or(|best| attempts < best.attempts)
+        {
+            self.challenges.insert(code.to_string(), entry.clone());
+            new.challenge_best = true;
+        }
+
+        (new.best_score, new.fewest_attempts) = self.records.entry(difficulty).or_default().update(&entry);
+        new
+    }
+
+    /// Records a win in a range band
+    /// Parameters:
+    ///   band: &str - label of the band the round was played in
+    ///   player: &str - who won
+    ///   score: u32 - points earned
+    ///   attempts: u32 - attempts taken
+    /// Returns:
+    ///   Whether either of the band's records was beaten
+    pub fn record_band_win(&mut self, band: &str, player: &str, score: u32, attempts: u32) -> bool {
+        let entry = ScoreEntry { player: player.to_string(), score, attempts };
+        let (best_score, fewest_attempts) = self.bands.entry(band.to_string()).or_default().update(&entry);
+        best_score || fewest_attempts
+    }
+
+    /// Human-readable summary of every difficulty with records
+    pub fn summary_lines(&self) -> Vec<String> {
+        self.records
+            .iter()
+            .map(|(difficulty, records)| {
+                let best = match &records.best_score {
+                    Some(entry) => format!("best score {} ({}, {} attempts)", entry.score, entry.player, entry.attempts),
+                    None => String::from("best score -"),
+                };
+                let fewest = match &records.fewest_attempts {
+                    Some(entry) => format!("fewest guesses {} ({})", entry.attempts, entry.player),
+                    None => String::from("fewest guesses -"),
+                };
+                format!("{}: {}; {}", difficulty, best, fewest)
+            })
+            .collect()
+    }
+
+    /// Converts the scoreboard to its JSON save format
+    pub fn to_json(&self) -> Json {
+        let difficulties = self
+            .records
+            .iter()
+            .map(|(difficulty, records)| (difficulty.name().to_string(), records_to_json(records)))
+            .collect();
+        let challenges = self.challenges.iter().map(|(code, entry)| (code.clone(), entry_to_json(entry))).collect();
+        let bands = self.bands.iter().map(|(band, records)| (band.clone(), records_to_json(records))).collect();
+
+        Json::Object(vec![
+            (String::from("version"), Json::Number(SCOREBOARD_VERSION as f64)),
+            (String::from("difficulties"), Json::Object(difficulties)),
+            (String::from("challenges"), Json::Object(challenges)),
+            (String::from("bands"), Json::Object(bands)),
+        ])
+    }
+
+    /// Reads a scoreboard from its JSON save format
+    /// Files without a version are the older layout holding one entry per
+    /// difficulty; that entry becomes the best-score record only
+    pub fn from_json(value: &Json) -> Result<Scoreboard, String> {
+        let mut scoreboard = Scoreboard::default();
+
+        match value.get("version").and_then(Json::as_u64) {
+            Some(SCOREBOARD_VERSION) => {
+                let difficulties = value
+                    .get("difficulties")
+                    .and_then(Json::as_object)
+                    .ok_or("scoreboard is missing its difficulties")?;
+                for (name, records) in difficulties {
+                    let Some(difficulty) = Difficulty::from_name(name) else { continue };
+                    scoreboard.records.insert(difficulty, records_from_json(records)?);
+                }
+                // Older version-2 files have no challenges or bands yet
+                if let Some(challenges) = value.get("challenges") {
+                    let challenges = challenges.as_object().ok_or("scoreboard challenges are not an object")?;
+                    for (code, entry) in challenges {
+                        scoreboard.challenges.insert(code.clone(), entry_from_json(entry)?);
+                    }
+                }
+                if let Some(bands) = value.get("bands") {
+                    let bands = bands.as_object().ok_or("scoreboard bands are not an object")?;
+                    for (band, records) in bands {
+                        scoreboard.bands.insert(band.clone(), records_from_json(records)?);
+                    }
+                }
+            },
+            Some(version) => return Err(format!("unsupported scoreboard version {}", version)),
+            None => {
+                let legacy = value.as_object().ok_or("scoreboard is not a JSON object")?;
+                for (name, record) in legacy {
+                    let Some(difficulty) = Difficulty::from_name(name) else { continue };
+                    scoreboard.records.entry(difficulty).or_default().best_score = Some(entry_from_json(record)?);
+                }
+            },
+        }
+
+        Ok(scoreboard)
+    }
+}
+
+fn records_to_json(records: &DifficultyRecords) -> Json {
+    let mut fields = Vec::new();
+    if let Some(entry) = &records.best_score {
+        fields.push((String::from("best_score"), entry_to_json(entry)));
+    }
+    if let Some(entry) = &records.fewest_attempts {
+        fields.push((String::from("fewest_attempts"), entry_to_json(entry)));
+    }
+    Json::Object(fields)
+}
+
+fn records_from_json(value: &Json) -> Result<DifficultyRecords, String> {
+    Ok(DifficultyRecords {
+        best_score: value.get("best_score").map(entry_from_json).transpose()?,
+        fewest_attempts: value.get("fewest_attempts").map(entry_from_json).transpose()?,
+    })
+}
+
+fn entry_to_json(entry: &ScoreEntry) -> Json {
+    Json::Object(vec![
+        (String::from("player"), Json::String(entry.player.clone())),
+        (String::from("score"), Json::Number(entry.score as f64)),
+        (String::from("attempts"), Json::Number(entry.attempts as f64)),
+    ])
+}
+
+fn entry_from_json(value: &Json) -> Result<ScoreEntry, String> {
+    Ok(ScoreEntry {
+        player: value.get("player").and_then(Json::as_str).unwrap_or("Player").to_string(),
+        score: value.get("score").and_then(Json::as_u64).ok_or("score entry is missing its score")? as u32,
+        attempts: value.get("attempts").and_then(Json::as_u64).ok_or("score entry is missing its attempts")? as u32,
+    })
+}
+
+/// Path of the scoreboard file inside a data directory
+pub fn scoreboard_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(SCOREBOARD_FILE)
+}
+
+/// Loads the scoreboard; a missing file is an empty scoreboard
+/// A file that cannot be understood is moved aside to `<name>.bak` so the
+/// next save starts clean; the error says where it went
+pub fn load_scores(path: &Path) -> Result<Scoreboard, String> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Scoreboard::default()),
+        Err(e) => return Err(e.to_string()),
+    };
+    json::parse(&text).and_then(|value| Scoreboard::from_json(&value)).map_err(|e| {
+        let backup = backup_path(path);
+        match fs::rename(path, &backup) {
+            Ok(()) => format!("{} (moved the bad file to {}; starting with an empty scoreboard)", e, backup.display()),
+            Err(rename_error) => format!("{} (could not back it up: {})", e, rename_error),
+        }
+    })
+}
+
+/// Where a corrupt save file is moved: the same path with `.bak` appended
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".bak");
+    PathBuf::from(name)
+}
+
+/// Saves the scoreboard, creating the data directory if needed
+pub fn save_scores(path: &Path, scoreboard: &Scoreboard) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    json::save(path, &scoreboard.to_json())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corrupt_scoreboard_is_backed_up_and_starts_empty() {
+        let dir = std::env::temp_dir().join(format!("guessing-game-corrupt-scores-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = scoreboard_path(&dir);
+        fs::write(&path, "{ not json").unwrap();
+
+        let error = load_scores(&path).err().unwrap();
+        assert!(error.contains("moved the bad file"));
+        assert!(backup_path(&path).exists());
+        assert!(!path.exists());
+
+        let scoreboard = load_scores(&path).unwrap();
+        assert!(scoreboard.records.is_empty());
+        assert!(scoreboard.challenges.is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn score_and_attempt_records_update_independently() {
+        let mut scoreboard = Scoreboard::default();
+        scoreboard.record_win(Difficulty::Medium, "Ada", 80, 5, None);
+
+        // Hints cost points but not attempts: fewer guesses, lower score
+        let new = scoreboard.record_win(Difficulty::Medium, "Bo", 40, 3, None);
+        assert_eq!(new, NewRecords { best_score: false, fewest_attempts: true, challenge_best: false });
+
+        let new = scoreboard.record_win(Difficulty::Medium, "Cy", 95, 4, None);
+        assert_eq!(new, NewRecords { best_score: true, fewest_attempts: false, challenge_best: false });
+
+        let records = &scoreboard.records[&Difficulty::Medium];
+        assert_eq!(records.best_score.as_ref().unwrap().player, "Cy");
+        assert_eq!(records.fewest_attempts.as_ref().unwrap().player, "Bo");
+    }
+
+    #[test]
+    fn legacy_file_fills_only_the_best_score() {
+        let legacy = json::parse(r#"{"easy": {"player": "Ada", "score": 70, "attempts": 4}}"#).unwrap();
+        let scoreboard = Scoreboard::from_json(&legacy).unwrap();
+        let records = &scoreboard.records[&Difficulty::Easy];
+        assert_eq!(records.best_score, Some(ScoreEntry { player: String::from("Ada"), score: 70, attempts: 4 }));
+        assert_eq!(records.fewest_attempts, None);
+    }
+
+    #[test]
+    fn challenge_keeps_only_the_better_attempt() {
+        let mut scoreboard = Scoreboard::default();
+        let code = challenge_code(42, (1.0, 100.0), GameMode::Integer);
+        let other = challenge_code(43, (1.0, 100.0), GameMode::Integer);
+
+        assert!(scoreboard.record_win(Difficulty::Medium, "Ada", 60, 6, Some(&code)).challenge_best);
+        assert!(!scoreboard.record_win(Difficulty::Medium, "Bo", 90, 7, Some(&code)).challenge_best);
+        assert!(scoreboard.record_win(Difficulty::Medium, "Cy", 50, 4, Some(&code)).challenge_best);
+        assert!(scoreboard.record_win(Difficulty::Medium, "Bo", 40, 9, Some(&other)).challenge_best);
+
+        assert_eq!(scoreboard.challenges.len(), 2);
+        assert_eq!(scoreboard.challenges[&code].attempts, 4);
+        assert_eq!(scoreboard.challenges[&code].player, "Cy");
+        assert_eq!(scoreboard.challenges[&other].attempts, 9);
+    }
+}
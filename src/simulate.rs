@@ -0,0 +1,181 @@
+//! Batch simulation module
+//!
+//! Plays many headless games to help balance difficulty settings:
+//! - The auto-solver (bisection) or a random player that still uses the
+//!   too-big/too-small feedback
+//! - Secrets drawn from the session's seeded generator, so runs repeat
+//! - Mean, median and worst attempt counts, and the win rate under a cap
+//! - A text histogram of how many games took each number of attempts
+
+use std::collections::BTreeMap;
+use rand::Rng;
+use crate::config::{GameConfig, GameMode};
+use crate::utils::{auto_solve, gen_secret, make_winnable};
+
+/// Guesses a random player makes before giving up on a secret
+const RANDOM_GIVE_UP: u32 = 10_000;
+/// Widest histogram bar, in characters
+const BAR_WIDTH: usize = 40;
+
+/// Who plays the simulated games
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SimPlayer {
+    /// The bisecting auto-solver
+    Solver,
+    /// Guesses anywhere in the interval still open
+    Random,
+}
+
+impl SimPlayer {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SimPlayer::Solver => "auto-solver",
+            SimPlayer::Random => "random player",
+        }
+    }
+}
+
+/// What the `simulate` command should run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimulationPlan {
+    pub games: u32,
+    pub player: SimPlayer,
+    /// Games needing more attempts than this count as lost
+    pub cap: u32,
+}
+
+/// Aggregate results of a simulation
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationReport {
+    /// Attempts used by each game, in play order
+    pub attempts: Vec<u32>,
+    pub cap: u32,
+}
+
+impl SimulationReport {
+    pub fn mean(&self) -> f64 {
+        if self.attempts.is_empty() {
+            return 0.0;
+        }
+        self.attempts.iter().map(|&n| n as f64).sum::<f64>() / self.attempts.len() as f64
+    }
+
+    /// Middle attempt count (the average of the middle two for an even count)
+    pub fn median(&self) -> f64 {
+        let mut sorted = self.attempts.clone();
+        sorted.sort_unstable();
+        match sorted.len() {
+            0 => 0.0,
+            n if n % 2 == 1 => sorted[n / 2] as f64,
+            n => (sorted[n / 2 - 1] + sorted[n / 2]) as f64 / 2.0,
+        }
+    }
+
+    pub fn max(&self) -> u32 {
+        self.attempts.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Percentage of games finished within the attempt cap
+    pub fn win_rate(&self) -> f64 {
+        if self.attempts.is_empty() {
+            return 0.0;
+        }
+        let wins = self.attempts.iter().filter(|&&n| n <= self.cap).count();
+        wins as f64 / self.attempts.len() as f64 * 100.0
+    }
+
+    /// Summary lines followed by a histogram of attempt counts
+    pub fn describe(&self) -> String {
+        let mut text = format!(
+            "Games: {}\nAttempts: mean {:.2}, median {}, max {}\nWin rate within {} attempts: {:.1}%\n",
+            self.attempts.len(),
+            self.mean(),
+            self.median(),
+            self.max(),
+            self.cap,
+            self.win_rate()
+        );
+        let mut counts: BTreeMap<u32, usize> = BTreeMap::new();
+        for &n in &self.attempts {
+            *counts.entry(n).or_default() += 1;
+        }
+        let most = counts.values().copied().max().unwrap_or(1);
+        for (attempts, count) in counts {
+            let bar = "#".repeat((count * BAR_WIDTH).div_ceil(most));
+            text.push_str(&format!("{:>4} | {:<width$} {}\n", attempts, bar, count, width = BAR_WIDTH));
+        }
+        text
+    }
+}
+
+/// Plays the planned number of games headlessly
+/// Parameters:
+///   config: &GameConfig - range, mode and tolerance for the secrets
+///   plan: SimulationPlan - games, player and attempt cap
+///   rng: &mut impl Rng - draws secrets (and the random player's guesses)
+pub fn simulate(config: &GameConfig, plan: SimulationPlan, rng: &mut impl Rng) -> SimulationReport {
+    let range = (config.range_start, config.range_end);
+    let mut config = config.clone();
+    make_winnable(&mut config, range);
+    let config = &config;
+    let attempts = (0..plan.games)
+        .map(|_| {
+            let secret = gen_secret(rng, range.0, range.1, config);
+            match plan.player {
+                SimPlayer::Solver => auto_solve(secret, range, config).len() as u32,
+                SimPlayer::Random => random_player(secret, range, config, rng),
+            }
+        })
+        .collect();
+    SimulationReport { attempts, cap: plan.cap }
+}
+
+/// Attempts a random player needs, narrowing the interval on each miss
+fn random_player(secret: f64, range: (f64, f64), config: &GameConfig, rng: &mut impl Rng) -> u32 {
+    let (mut low, mut high) = range;
+    for attempt in 1..=RANDOM_GIVE_UP {
+        let guess = match config.mode {
+            GameMode::Integer => rng.random_range(low.ceil() as i64..=high.floor() as i64) as f64,
+            GameMode::Decimal => rng.random_range(low..=high),
+        };
+        if (guess - secret).abs() <= config.tolerance {
+            return attempt;
+        }
+        let step = if config.mode == GameMode::Integer { 1.0 } else { 0.0 };
+        if guess < secret {
+            low = guess + step;
+        } else {
+            high = guess - step;
+        }
+    }
+    RANDOM_GIVE_UP
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::seeded_rng;
+
+    fn solver_plan() -> SimulationPlan {
+        SimulationPlan { games: 200, player: SimPlayer::Solver, cap: 6 }
+    }
+
+    #[test]
+    fn seeded_simulation_is_stable() {
+        let config = GameConfig::default();
+        let report = simulate(&config, solver_plan(), &mut seeded_rng(1));
+        assert_eq!(report, simulate(&config, solver_plan(), &mut seeded_rng(1)));
+        assert_eq!(report.attempts.len(), 200);
+        assert_eq!((report.mean(), report.median(), report.max()), (4.865, 5.0, 7));
+        assert_eq!(report.win_rate(), 99.0);
+    }
+
+    #[test]
+    fn aggregates_match_the_attempts() {
+        let report = SimulationReport { attempts: vec![3, 1, 4, 1, 5, 9], cap: 4 };
+        assert_eq!(report.mean(), 23.0 / 6.0);
+        assert_eq!(report.median(), 3.5);
+        assert_eq!(report.max(), 9);
+        assert_eq!(report.win_rate(), 4.0 / 6.0 * 100.0);
+    }
+}
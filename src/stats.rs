@@ -0,0 +1,756 @@
+//! Session statistics module
+//!
+//! Tracks how the player is doing across rounds:
+//! - Games played and won
+//! - Attempt totals and best result
+//! - Win streaks
+//! - All-time stats persisted to disk, with merging from another machine
+//! - Markdown export for sharing
+//! - Fastest wins (speedrun records) per difficulty
+//! - Guess heatmap (where in the range guesses land), exported as CSV
+//! - Personal bests report shown at exit
+//! - Average attempts per stretch of the range, to spot weak regions
+
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use crate::config::Difficulty;
+use crate::history::{calendar_date, unix_now};
+use crate::json::{self, Json};
+
+/// Current stats file layout version
+const STATS_VERSION: u64 = 1;
+/// Stats file name inside the data directory
+pub const STATS_FILE: &str = "stats.json";
+/// Number of equal-width bins the guess heatmap splits a range into
+pub const HEATMAP_BINS: usize = 10;
+
+/// Running totals for the current session
+#[derive(Default)]
+pub struct SessionStats {
+    pub games: u32,
+    pub wins: u32,
+    pub total_attempts: u32,
+    pub best_attempts: Option<u32>,
+    pub current_streak: u32,
+    pub best_streak: u32,
+    /// Points earned across all rounds this session
+    pub score: u32,
+    /// Totals across every session, saved on exit
+    pub all_time: PersistedStats,
+    /// Games and wins per range band, keyed by the band's label
+    pub bands: BTreeMap<String, BandTally>,
+    /// Set after the first failed save; the session then keeps its data in memory only
+    pub saves_disabled: Cell<bool>,
+}
+
+/// Games and wins in one range band
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct BandTally {
+    pub games: u32,
+    pub wins: u32,
+}
+
+impl SessionStats {
+    /// Records the outcome of one finished round
+    /// Parameters:
+    ///   won: bool - whether the round was won
+    ///   attempts: u32 - attempts used in the round
+    pub fn record_round(&mut self, won: bool, attempts: u32) {
+        self.record(won, attempts, true);
+    }
+
+    /// Records another try at a game that was already counted
+    /// Used when a lost secret is retried as a continuation: attempts and
+    /// a win still count, but the game itself is not counted again
+    pub fn record_continued_round(&mut self, won: bool, attempts: u32) {
+        self.record(won, attempts, false);
+    }
+
+    fn record(&mut self, won: bool, attempts: u32, new_game: bool) {
+        self.all_time.record_round(won, attempts, new_game);
+        if new_game {
+            self.games += 1;
+        }
+        self.total_attempts += attempts;
+
+        if won {
+            self.wins += 1;
+            self.current_streak += 1;
+            self.best_streak = self.best_streak.max(self.current_streak);
+            self.best_attempts = Some(match self.best_attempts {
+                Some(best) => best.min(attempts),
+                None => attempts,
+            });
+        } else {
+            self.current_streak = 0;
+        }
+    }
+
+    /// Counts a finished game towards its range band
+    /// Parameters:
+    ///   band: &str - label of the band the game was played in
+    ///   won: bool - whether the game was won
+    pub fn record_band(&mut self, band: &str, won: bool) {
+        let tally = self.bands.entry(band.to_string()).or_default();
+        tally.games += 1;
+        if won {
+            tally.wins += 1;
+        }
+    }
+
+    /// Applies a double-or-nothing wager on a round's score
+    /// Parameters:
+    ///   stake: u32 - the round score that was risked (already in the total)
+    ///   won: bool - whether the bonus round was won
+    pub fn settle_wager(&mut self, stake: u32, won: bool) {
+        if won {
+            self.score += stake;
+        } else {
+            self.score = self.score.saturating_sub(stake);
+        }
+    }
+
+    /// Percentage of games won (0.0 when nothing has been played)
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            self.wins as f64 / self.games as f64 * 100.0
+        }
+    }
+
+    /// Average attempts per game (0.0 when nothing has been played)
+    pub fn avg_attempts(&self) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            self.total_attempts as f64 / self.games as f64
+        }
+    }
+}
+
+/// Fastest win on one difficulty
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Speedrun {
+    /// Wall-clock time from round start to the correct guess
+    pub time: Duration,
+    pub attempts: u32,
+}
+
+impl Speedrun {
+    /// Whether this run beats another: less time, then fewer attempts
+    pub fn beats(&self, other: &Speedrun) -> bool {
+        (self.time, self.attempts) < (other.time, other.attempts)
+    }
+}
+
+/// Totals kept across sessions
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PersistedStats {
+    pub games: u32,
+    pub wins: u32,
+    pub total_attempts: u32,
+    pub best_attempts: Option<u32>,
+    pub best_streak: u32,
+    /// Wins in a row so far, carried across sessions until a loss
+    pub current_streak: u32,
+    /// Games won on Hard, which unlock Insane
+    pub hard_wins: u32,
+    pub achievements: BTreeSet<String>,
+    /// Guesses per heatmap bin, by position in that game's range
+    pub guess_bins: [u32; HEATMAP_BINS],
+    /// Wins per heatmap bin, by where the secret sat in that game's range
+    pub win_bins: [u32; HEATMAP_BINS],
+    /// Attempts spent on those wins, per heatmap bin
+    pub win_attempts: [u32; HEATMAP_BINS],
+    /// When a round was last played, in seconds since the Unix epoch
+    pub last_played: Option<u64>,
+    /// Fastest win per difficulty
+    pub speedruns: BTreeMap<Difficulty, Speedrun>,
+    /// Highest score for a single game
+    pub best_score: Option<u32>,
+    /// Games played on the day of `last_played`
+    pub day_games: u32,
+    /// Most games played on one day (UTC)
+    pub most_games_in_day: u32,
+}
+
+impl PersistedStats {
+    /// Adds one finished round to the all-time totals
+    /// Parameters:
+    ///   won: bool - whether the round was won
+    ///   attempts: u32 - attempts used in the round
+    ///   new_game: bool - false for a continuation of a game already counted
+    pub fn record_round(&mut self, won: bool, attempts: u32, new_game: bool) {
+        let now = unix_now();
+        if new_game {
+            self.games += 1;
+            let same_day = self.last_played.is_some_and(|last| calendar_date(last) == calendar_date(now));
+            self.day_games = if same_day { self.day_games + 1 } else { 1 };
+            self.most_games_in_day = self.most_games_in_day.max(self.day_games);
+        }
+        self.total_attempts += attempts;
+        self.last_played = Some(now);
+
+        if won {
+            self.wins += 1;
+            self.current_streak += 1;
+            self.best_streak = self.best_streak.max(self.current_streak);
+            self.best_attempts = Some(self.best_attempts.map_or(attempts, |best| best.min(attempts)));
+            self.achievements.insert(String::from("First Win"));
+            if attempts == 1 {
+                self.achievements.insert(String::from("Bullseye"));
+            }
+            if self.current_streak >= 3 {
+                self.achievements.insert(String::from("Hat Trick"));
+            }
+        } else {
+            self.current_streak = 0;
+        }
+    }
+
+    /// Average attempts per game (None before the first game)
+    pub fn avg_attempts(&self) -> Option<f64> {
+        (self.games > 0).then(|| self.total_attempts as f64 / self.games as f64)
+    }
+
+    /// Counts a win towards unlocking harder difficulties
+    pub fn record_win_on(&mut self, difficulty: Difficulty) {
+        if difficulty == Difficulty::Hard {
+            self.hard_wins += 1;
+        }
+    }
+
+    /// Keeps a win as the speedrun record if it is the fastest on its difficulty
+    /// Returns:
+    ///   Whether it set a new record
+    pub fn record_speedrun(&mut self, difficulty: Difficulty, run: Speedrun) -> bool {
+        if self.speedruns.get(&difficulty).is_some_and(|best| !run.beats(best)) {
+            return false;
+        }
+        self.speedruns.insert(difficulty, run);
+        true
+    }
+
+    /// Keeps a game's score as the record if it is the highest yet
+    /// Returns:
+    ///   Whether it set a new record
+    pub fn record_score(&mut self, score: u32) -> bool {
+        if self.best_score.is_some_and(|best| score <= best) {
+            return false;
+        }
+        self.best_score = Some(score);
+        true
+    }
+
+    /// Counts one guess towards the heatmap
+    /// Parameters:
+    ///   guess: f64 - the guessed value
+    ///   range: (f64, f64) - the range the guess was made in
+    pub fn record_guess(&mut self, guess: f64, range: (f64, f64)) {
+        self.guess_bins[heatmap_bin(guess, range)] += 1;
+    }
+
+    /// Counts one win towards the bin its secret fell in
+    /// Parameters:
+    ///   secret: f64 - the secret that was found
+    ///   range: (f64, f64) - the range the game was played in
+    ///   attempts: u32 - attempts the win took
+    pub fn record_secret_win(&mut self, secret: f64, range: (f64, f64), attempts: u32) {
+        let bin = heatmap_bin(secret, range);
+        self.win_bins[bin] += 1;
+        self.win_attempts[bin] += attempts;
+    }
+
+    /// Average attempts to win with a secret in the given bin (None before any such win)
+    pub fn bin_average(&self, bin: usize) -> Option<f64> {
+        match self.win_bins[bin] {
+            0 => None,
+            wins => Some(self.win_attempts[bin] as f64 / wins as f64),
+        }
+    }
+
+    /// Folds another machine's stats into these
+    /// Counters are summed, records keep the better value and
+    /// achievements are combined
+    pub fn merge(&mut self, other: &PersistedStats) {
+        self.games += other.games;
+        self.wins += other.wins;
+        self.total_attempts += other.total_attempts;
+        self.best_attempts = match (self.best_attempts, other.best_attempts) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.best_streak = self.best_streak.max(other.best_streak);
+        self.best_score = self.best_score.max(other.best_score);
+        self.most_games_in_day = self.most_games_in_day.max(other.most_games_in_day);
+        self.hard_wins += other.hard_wins;
+        self.last_played = self.last_played.max(other.last_played);
+        for (&difficulty, &run) in &other.speedruns {
+            self.record_speedrun(difficulty, run);
+        }
+        self.achievements.extend(other.achievements.iter().cloned());
+        for (bin, count) in self.guess_bins.iter_mut().zip(other.guess_bins) {
+            *bin += count;
+        }
+        for (bin, count) in self.win_bins.iter_mut().zip(other.win_bins) {
+            *bin += count;
+        }
+        for (bin, count) in self.win_attempts.iter_mut().zip(other.win_attempts) {
+            *bin += count;
+        }
+    }
+
+    /// Converts the stats to their JSON save format
+    pub fn to_json(&self) -> Json {
+        let mut fields = vec![
+            (String::from("version"), Json::Number(STATS_VERSION as f64)),
+            (String::from("games"), Json::Number(self.games as f64)),
+            (String::from("wins"), Json::Number(self.wins as f64)),
+            (String::from("total_attempts"), Json::Number(self.total_attempts as f64)),
+            (String::from("best_streak"), Json::Number(self.best_streak as f64)),
+            (String::from("current_streak"), Json::Number(self.current_streak as f64)),
+            (String::from("hard_wins"), Json::Number(self.hard_wins as f64)),
+            (String::from("day_games"), Json::Number(self.day_games as f64)),
+            (String::from("most_games_in_day"), Json::Number(self.most_games_in_day as f64)),
+        ];
+        if let Some(best) = self.best_attempts {
+            fields.push((String::from("best_attempts"), Json::Number(best as f64)));
+        }
+        if let Some(best) = self.best_score {
+            fields.push((String::from("best_score"), Json::Number(best as f64)));
+        }
+        if let Some(last_played) = self.last_played {
+            fields.push((String::from("last_played"), Json::Number(last_played as f64)));
+        }
+        fields.push((
+            String::from("achievements"),
+            Json::Array(self.achievements.iter().cloned().map(Json::String).collect()),
+        ));
+        fields.push((
+            String::from("speedruns"),
+            Json::Object(
+                self.speedruns
+                    .iter()
+                    .map(|(difficulty, run)| {
+                        let record = Json::Object(vec![
+                            (String::from("seconds"), Json::Number(run.time.as_secs_f64())),
+                            (String::from("attempts"), Json::Number(run.attempts as f64)),
+                        ]);
+                        (difficulty.name().to_string(), record)
+                    })
+                    .collect(),
+            ),
+        ));
+        for (key, bins) in [("guess_bins", &self.guess_bins), ("win_bins", &self.win_bins), ("win_attempts", &self.win_attempts)] {
+            fields.push((String::from(key), Json::Array(bins.iter().map(|&count| Json::Number(count as f64)).collect())));
+        }
+        Json::Object(fields)
+    }
+
+    /// Reads stats from their JSON save format
+    pub fn from_json(value: &Json) -> Result<PersistedStats, String> {
+        match value.get("version").and_then(Json::as_u64) {
+            Some(STATS_VERSION) => {},
+            Some(version) => return Err(format!("unsupported stats version {}", version)),
+            None => return Err(String::from("not a stats file (no version)")),
+        }
+        let count = |key: &str| -> Result<u32, String> {
+            match value.get(key) {
+                None => Ok(0),
+                Some(field) => field.as_u64().map(|n| n as u32).ok_or(format!("stats field '{}' is not a count", key)),
+            }
+        };
+
+        let mut achievements = BTreeSet::new();
+        if let Some(list) = value.get("achievements") {
+            let list = list.as_array().ok_or("stats achievements are not a list")?;
+            for item in list {
+                achievements.insert(item.as_str().ok_or("achievement is not a string")?.to_string());
+            }
+        }
+
+        let bins = |key: &str| -> Result<[u32; HEATMAP_BINS], String> {
+            let mut bins = [0; HEATMAP_BINS];
+            if let Some(list) = value.get(key) {
+                let list = list.as_array().ok_or(format!("stats field '{}' is not a list", key))?;
+                if list.len() != HEATMAP_BINS {
+                    return Err(format!("expected {} entries in '{}', found {}", HEATMAP_BINS, key, list.len()));
+                }
+                for (bin, item) in bins.iter_mut().zip(list) {
+                    *bin = item.as_u64().ok_or(format!("entry in '{}' is not a count", key))? as u32;
+                }
+            }
+            Ok(bins)
+        };
+
+        let mut speedruns = BTreeMap::new();
+        if let Some(runs) = value.get("speedruns") {
+            let runs = runs.as_object().ok_or("stats speedruns are not an object")?;
+            for (name, run) in runs {
+                let difficulty = Difficulty::from_name(name).ok_or(format!("unknown speedrun difficulty '{}'", name))?;
+                let seconds = run
+                    .get("seconds")
+                    .and_then(Json::as_f64)
+                    .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+                    .ok_or("speedrun has no valid time")?;
+                let attempts = run.get("attempts").and_then(Json::as_u64).ok_or("speedrun has no attempt count")?;
+                speedruns.insert(difficulty, Speedrun { time: Duration::from_secs_f64(seconds), attempts: attempts as u32 });
+            }
+        }
+
+        Ok(PersistedStats {
+            games: count("games")?,
+            wins: count("wins")?,
+            total_attempts: count("total_attempts")?,
+            best_attempts: value.get("best_attempts").and_then(Json::as_u64).map(|n| n as u32),
+            best_streak: count("best_streak")?,
+            current_streak: count("current_streak")?,
+            hard_wins: count("hard_wins")?,
+            achievements,
+            guess_bins: bins("guess_bins")?,
+            win_bins: bins("win_bins")?,
+            win_attempts: bins("win_attempts")?,
+            last_played: value.get("last_played").and_then(Json::as_u64),
+            speedruns,
+            best_score: value.get("best_score").and_then(Json::as_u64).map(|n| n as u32),
+            day_games: count("day_games")?,
+            most_games_in_day: count("most_games_in_day")?,
+        })
+    }
+}
+
+/// Table of all-time records, with "—" for any not set yet
+/// Parameters:
+///   stats: &PersistedStats - all-time stats to report on
+pub fn personal_bests_report(stats: &PersistedStats) -> String {
+    let unset = || String::from("—");
+    let count = |n: u32| if n == 0 { unset() } else { n.to_string() };
+    let fastest = stats
+        .speedruns
+        .iter()
+        .min_by_key(|(_, run)| run.time)
+        .map_or_else(unset, |(difficulty, run)| format!("{:.1}s ({})", run.time.as_secs_f64(), difficulty));
+    let rows = [
+        ("Fewest attempts", stats.best_attempts.map_or_else(unset, |n| n.to_string())),
+        ("Fastest time", fastest),
+        ("Longest streak", count(stats.best_streak)),
+        ("Highest score", stats.best_score.map_or_else(unset, |n| n.to_string())),
+        ("Most games in a day", count(stats.most_games_in_day)),
+    ];
+    let mut report = String::from("Personal bests\n");
+    for (label, value) in rows {
+        report.push_str(&format!("  {:<21}{}\n", label, value));
+    }
+    report
+}
+
+/// Average attempts per stretch of the range, with the weakest one called out
+/// Stretches are the heatmap bins, as a share of each game's range
+/// Parameters:
+///   stats: &PersistedStats - all-time stats holding the per-bin wins
+pub fn weaknesses_report(stats: &PersistedStats) -> String {
+    let wins: u32 = stats.win_bins.iter().sum();
+    if wins == 0 {
+        return String::from("No wins recorded yet.\n");
+    }
+    let overall = stats.win_attempts.iter().sum::<u32>() as f64 / wins as f64;
+    let width = 100 / HEATMAP_BINS;
+    let mut report = format!("Average attempts per stretch of the range (overall {:.2})\n", overall);
+    let mut weakest: Option<(usize, f64)> = None;
+    for bin in 0..HEATMAP_BINS {
+        let label = format!("{}–{}%", bin * width, (bin + 1) * width);
+        match stats.bin_average(bin) {
+            Some(average) => {
+                let extra = (average / overall - 1.0) * 100.0;
+                let plural = if stats.win_bins[bin] == 1 { "" } else { "s" };
+                report.push_str(&format!("  {:<9}{:>6.2} over {} win{} ({:+.0}%)\n", label, average, stats.win_bins[bin], plural, extra));
+                if extra > 0.0 && weakest.is_none_or(|(_, worst)| extra > worst) {
+                    weakest = Some((bin, extra));
+                }
+            },
+            None => report.push_str(&format!("  {:<9}     —\n", label)),
+        }
+    }
+    match weakest {
+        Some((bin, extra)) => report.push_str(&format!(
+            "You take {:.0}% more guesses for numbers {}–{}% of the way up the range.\n",
+            extra,
+            bin * width,
+            (bin + 1) * width
+        )),
+        None => report.push_str("No stretch of the range stands out.\n"),
+    }
+    report
+}
+
+/// One-line summary of the all-time record, shown on launch
+/// A fresh install (nothing played yet) gets a plain welcome instead
+pub fn startup_banner(stats: &PersistedStats) -> String {
+    if stats.games == 0 {
+        return String::from("Welcome, new player!");
+    }
+    let mut banner = format!(
+        "Welcome back! {} games, {:.1}% won",
+        stats.games,
+        stats.wins as f64 / stats.games as f64 * 100.0
+    );
+    if let Some(best) = stats.best_attempts {
+        banner.push_str(&format!(", best {} attempts", best));
+    }
+    banner.push_str(&format!(", current streak {}", stats.current_streak));
+    if let Some(last_played) = stats.last_played {
+        banner.push_str(&format!(", last played {}", calendar_date(last_played)));
+    }
+    banner
+}
+
+/// Which heatmap bin a guess falls in
+/// The range end goes in the last bin; a zero-width range uses the first
+fn heatmap_bin(guess: f64, range: (f64, f64)) -> usize {
+    let (start, end) = range;
+    if end <= start {
+        return 0;
+    }
+    let fraction = ((guess - start) / (end - start)).clamp(0.0, 1.0);
+    ((fraction * HEATMAP_BINS as f64) as usize).min(HEATMAP_BINS - 1)
+}
+
+/// Path of the stats file inside a data directory
+pub fn stats_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(STATS_FILE)
+}
+
+/// Loads all-time stats; a missing file means a fresh start
+pub fn load_stats(path: &Path) -> Result<PersistedStats, String> {
+    match fs::read_to_string(path) {
+        Ok(text) => PersistedStats::from_json(&json::parse(&text)?),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(PersistedStats::default()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Saves all-time stats, creating the data directory if needed
+pub fn save_stats(path: &Path, stats: &PersistedStats) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    json::save(path, &stats.to_json())
+}
+
+/// Merges another stats file into the local one
+/// The other file is fully validated before anything local is written
+/// Parameters:
+///   local: path of this machine's stats file
+///   other: path of the file to merge in
+/// Returns:
+///   The merged stats, or why the merge was refused
+pub fn merge_stats_files(local: &Path, other: &Path) -> Result<PersistedStats, String> {
+    let text = fs::read_to_string(other).map_err(|e| format!("Could not read {}: {}", other.display(), e))?;
+    let incoming = PersistedStats::from_json(&json::parse(&text)?)?;
+    let mut merged = load_stats(local)?;
+    merged.merge(&incoming);
+    save_stats(local, &merged).map_err(|e| format!("Could not save {}: {}", local.display(), e))?;
+    Ok(merged)
+}
+
+/// Renders stats as a GitHub-flavored markdown table
+/// Parameters:
+///   stats: &SessionStats - stats to render
+///   player: &str - player name for the first column
+/// Returns:
+///   Header, separator and one data row, newline-terminated
+pub fn markdown_table(stats: &SessionStats, player: &str) -> String {
+    let best = match stats.best_attempts {
+        Some(best) => best.to_string(),
+        None => String::from("-"),
+    };
+
+    let mut table = String::new();
+    table.push_str("| Player | Games | Wins | Win rate | Avg attempts | Best | Streak |\n");
+    table.push_str("|---|---:|---:|---:|---:|---:|---:|\n");
+    table.push_str(&format!(
+        "| {} | {} | {} | {:.1}% | {:.2} | {} | {} |\n",
+        escape_markdown_cell(player),
+        stats.games,
+        stats.wins,
+        stats.win_rate(),
+        stats.avg_attempts(),
+        best,
+        stats.best_streak,
+    ));
+    table
+}
+
+/// Writes the markdown stats table to a file
+/// Parameters:
+///   path: destination file (overwritten)
+///   stats: &SessionStats - stats to export
+///   player: &str - player name for the table
+pub fn export_markdown(path: &Path, stats: &SessionStats, player: &str) -> io::Result<()> {
+    fs::write(path, markdown_table(stats, player))
+}
+
+/// Renders the guess heatmap as CSV
+/// Bins are labelled by the share of the range they cover
+/// Returns:
+///   Header plus one row per bin, newline-terminated
+pub fn heatmap_csv(stats: &PersistedStats) -> String {
+    let width = 100 / HEATMAP_BINS;
+    let mut csv = String::from("from_pct,to_pct,guesses\n");
+    for (i, count) in stats.guess_bins.iter().enumerate() {
+        csv.push_str(&format!("{},{},{}\n", i * width, (i + 1) * width, count));
+    }
+    csv
+}
+
+/// Writes the guess heatmap to a CSV file
+/// Parameters:
+///   path: destination file (overwritten)
+///   stats: &PersistedStats - all-time stats holding the heatmap
+pub fn export_heatmap(path: &Path, stats: &PersistedStats) -> io::Result<()> {
+    fs::write(path, heatmap_csv(stats))
+}
+
+/// Escapes characters that would break a markdown table cell
+fn escape_markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_table_has_header_and_escaped_row() {
+        let mut stats = SessionStats::default();
+        stats.record_round(true, 4);
+        stats.record_round(false, 6);
+        let table = markdown_table(&stats, "Ann|Bo");
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "| Player | Games | Wins | Win rate | Avg attempts | Best | Streak |");
+        assert_eq!(lines[1], "|---|---:|---:|---:|---:|---:|---:|");
+        assert_eq!(lines[2], "| Ann\\|Bo | 2 | 1 | 50.0% | 5.00 | 4 | 1 |");
+    }
+
+    /// Stats as they would be after saving and loading them again
+    fn reloaded(stats: &PersistedStats) -> PersistedStats {
+        PersistedStats::from_json(&json::parse(&stats.to_json().to_pretty()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn streak_survives_a_restart() {
+        let mut first = SessionStats::default();
+        first.record_round(true, 3);
+        let mut second = SessionStats { all_time: reloaded(&first.all_time), ..SessionStats::default() };
+        second.record_round(true, 5);
+        assert_eq!(second.all_time.current_streak, 2);
+        assert_eq!(second.all_time.best_streak, 2);
+    }
+
+    #[test]
+    fn loss_breaks_the_streak_but_keeps_the_best() {
+        let mut stats = PersistedStats::default();
+        stats.record_round(true, 2, true);
+        stats.record_round(true, 4, true);
+        stats.record_round(false, 7, true);
+        assert_eq!(stats.current_streak, 0);
+        assert_eq!(stats.best_streak, 2);
+    }
+
+    #[test]
+    fn old_stats_without_a_streak_load_as_zero() {
+        let old = json::parse(r#"{"version": 1, "games": 3, "wins": 2}"#).unwrap();
+        let stats = PersistedStats::from_json(&old).unwrap();
+        assert_eq!(stats.current_streak, 0);
+        assert_eq!(stats.games, 3);
+    }
+
+    #[test]
+    fn games_in_a_day_count_games_not_continuations() {
+        let mut stats = PersistedStats::default();
+        stats.record_round(false, 3, true);
+        stats.record_round(true, 2, false);
+        stats.record_round(true, 4, true);
+        assert_eq!(stats.day_games, 2);
+        assert_eq!(stats.most_games_in_day, 2);
+    }
+
+    #[test]
+    fn personal_bests_report_lists_every_record() {
+        let mut stats = PersistedStats {
+            best_attempts: Some(3),
+            best_streak: 4,
+            best_score: Some(120),
+            most_games_in_day: 6,
+            ..PersistedStats::default()
+        };
+        stats.speedruns.insert(Difficulty::Hard, Speedrun { time: Duration::from_millis(12_300), attempts: 7 });
+        let report = personal_bests_report(&stats);
+        assert!(report.contains("Fewest attempts      3\n"));
+        assert!(report.contains("Fastest time         12.3s (Hard)\n"));
+        assert!(report.contains("Longest streak       4\n"));
+        assert!(report.contains("Highest score        120\n"));
+        assert!(report.contains("Most games in a day  6\n"));
+        assert!(!report.contains('—'));
+    }
+
+    #[test]
+    fn personal_bests_report_shows_placeholders_for_a_new_player() {
+        let report = personal_bests_report(&PersistedStats::default());
+        assert_eq!(report.lines().count(), 6);
+        assert!(report.lines().skip(1).all(|line| line.ends_with('—')));
+    }
+
+    #[test]
+    fn bin_average_covers_the_wins_in_that_bin() {
+        let mut stats = PersistedStats::default();
+        stats.record_secret_win(72.0, (0.0, 100.0), 4);
+        stats.record_secret_win(75.0, (0.0, 100.0), 6);
+        stats.record_secret_win(7.5, (0.0, 10.0), 11);
+        stats.record_secret_win(15.0, (0.0, 100.0), 3);
+        assert_eq!(stats.win_bins[7], 3);
+        assert_eq!(stats.bin_average(7), Some(7.0));
+        assert_eq!(stats.bin_average(1), Some(3.0));
+        assert_eq!(stats.bin_average(0), None);
+    }
+
+    #[test]
+    fn weaknesses_report_calls_out_the_slowest_bin() {
+        let mut stats = PersistedStats::default();
+        stats.record_secret_win(75.0, (0.0, 100.0), 14);
+        stats.record_secret_win(15.0, (0.0, 100.0), 6);
+        let report = weaknesses_report(&stats);
+        assert!(report.contains("70–80%    14.00 over 1 win (+40%)"));
+        assert!(report.ends_with("You take 40% more guesses for numbers 70–80% of the way up the range.\n"));
+        assert_eq!(weaknesses_report(&PersistedStats::default()), "No wins recorded yet.\n");
+    }
+
+    #[test]
+    fn startup_banner_sums_up_the_record() {
+        let stats = PersistedStats {
+            games: 8,
+            wins: 6,
+            best_attempts: Some(3),
+            current_streak: 2,
+            last_played: Some(1_709_164_800),
+            ..PersistedStats::default()
+        };
+        assert_eq!(
+            startup_banner(&stats),
+            "Welcome back! 8 games, 75.0% won, best 3 attempts, current streak 2, last played 2024-02-29"
+        );
+    }
+
+    #[test]
+    fn fresh_install_gets_a_plain_welcome() {
+        let missing = std::env::temp_dir().join(format!("guessing-game-fresh-{}", std::process::id())).join(STATS_FILE);
+        let stats = load_stats(&missing).unwrap();
+        assert_eq!(startup_banner(&stats), "Welcome, new player!");
+    }
+}
@@ -0,0 +1,79 @@
+//! Hard-hint trainer module
+//!
+//! Practice for solving the hard-hint equations:
+//! - Picks a random secret and a random hard-hint equation
+//! - Shows the equation's value and asks the player for S
+//! - Grades the answer against the secret within the game's tolerance
+//! - Keeps a running score until the player stops
+
+use std::io::{self, Write};
+use colored::Colorize;
+use rand::Rng;
+use crate::config::{GameConfig, GameMode};
+use crate::error::GameError;
+use crate::input;
+use crate::utils::{format_number, gen_secret, hard_hints, winnable_tolerance};
+
+/// How the player did in the trainer
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TrainerScore {
+    pub correct: u32,
+    pub asked: u32,
+}
+
+/// Whether a solved-for S is close enough to the secret
+/// Parameters:
+///   answer: f64 - the player's value for S
+///   secret: f64 - the actual secret
+///   tolerance: f64 - largest accepted difference
+pub fn grade_answer(answer: f64, secret: f64, tolerance: f64) -> bool {
+    (answer - secret).abs() <= tolerance
+}
+
+/// Asks hard-hint equations until the player stops or input ends
+/// Parameters:
+///   config: &GameConfig - range, mode and tolerance for the secrets
+///   rng: &mut impl Rng - picks secrets and equations
+/// Returns:
+///   The final score
+pub fn run(config: &GameConfig, rng: &mut impl Rng) -> Result<TrainerScore, GameError> {
+    let range = (config.range_start, config.range_end);
+    // Decimal secrets are shown rounded, so an exact answer is impossible
+    let tolerance = match config.mode {
+        GameMode::Decimal if config.tolerance == 0.0 => winnable_tolerance(range),
+        _ => config.tolerance,
+    };
+    let equations = hard_hints();
+    let mut score = TrainerScore::default();
+
+    println!("Hard-hint trainer: solve each equation for S (empty line or q to stop).");
+    'question: loop {
+        let secret = gen_secret(rng, range.0, range.1, config);
+        let (hint, expr) = &equations[rng.random_range(0..equations.len())];
+        println!("\n{}", hint.trim().replace("{}", &format!("{:.2}", expr(secret))).purple());
+
+        let answer = loop {
+            print!("S = ");
+            io::stdout().flush()?;
+            let Ok(line) = input::read_line() else { break 'question };
+            let line = input::normalize_input(&line);
+            if line.is_empty() || line.eq_ignore_ascii_case("q") {
+                break 'question;
+            }
+            match line.parse::<f64>() {
+                Ok(answer) => break answer,
+                Err(_) => println!("{}", "Please enter a number.".red()),
+            }
+        };
+
+        score.asked += 1;
+        if grade_answer(answer, secret, tolerance) {
+            score.correct += 1;
+            println!("{}", "Correct!".green().bold());
+        } else {
+            println!("{}", format!("Not quite - S was {}.", format_number(secret)).red());
+        }
+        println!("Trainer score: {}/{}", score.correct, score.asked);
+    }
+    Ok(score)
+}
@@ -0,0 +1,113 @@
+//! Surprise-mode twists
+//!
+//! In surprise mode every new game gets one random rule change:
+//! - Time Attack: a tight time limit
+//! - Whole Numbers: integer-only secrets
+//! - High Five: the secret is a multiple of 5
+//! - Hint Bonanza: double the hint budget
+//!
+//! A twist the game's range can't support (no multiple of 5, or no whole
+//! number in it) is skipped and the plain game is played instead
+
+use rand::Rng;
+use std::time::Duration;
+use crate::config::{GameConfig, GameMode};
+
+/// Time limit used by Time Attack when none is configured
+const TIME_ATTACK_LIMIT: Duration = Duration::from_secs(30);
+/// Hint budget doubled by Hint Bonanza when none is configured
+const BONANZA_BASE_BUDGET: u32 = 2;
+
+/// A rule change for one game
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Twist {
+    TimeAttack,
+    WholeNumbers,
+    HighFive,
+    HintBonanza,
+}
+
+const TWISTS: [Twist; 4] = [Twist::TimeAttack, Twist::WholeNumbers, Twist::HighFive, Twist::HintBonanza];
+
+impl Twist {
+    /// Name announced at the start of the game
+    pub fn name(&self) -> &'static str {
+        match self {
+            Twist::TimeAttack => "Time Attack!",
+            Twist::WholeNumbers => "Whole Numbers Only!",
+            Twist::HighFive => "High Five (the secret is a multiple of 5)!",
+            Twist::HintBonanza => "Hint Bonanza (double hints)!",
+        }
+    }
+
+    /// Returns a copy of the config with this twist applied
+    /// Parameters:
+    ///   config: &GameConfig - settings for the game
+    ///   range: (f64, f64) - the game's range
+    /// Returns:
+    ///   The twisted config, or None if the twist can't apply to this range
+    pub fn apply(&self, config: &GameConfig, range: (f64, f64)) -> Option<GameConfig> {
+        let bounds = config.game_range(range.0, range.1);
+        let mut twisted = config.clone();
+        match self {
+            Twist::TimeAttack => {
+                twisted.time_limit = Some(match config.time_limit {
+                    Some(limit) => limit / 2,
+                    None => TIME_ATTACK_LIMIT,
+                });
+            },
+            Twist::WholeNumbers => {
+                bounds.whole_bounds(1.0)?;
+                twisted.mode = GameMode::Integer;
+            },
+            Twist::HighFive => {
+                bounds.whole_bounds(5.0)?;
+                twisted.multiple_of = Some(5);
+            },
+            Twist::HintBonanza => {
+                twisted.hint_budget = Some(config.hint_budget.unwrap_or(BONANZA_BASE_BUDGET) * 2);
+            },
+        }
+        Some(twisted)
+    }
+}
+
+/// Picks a twist using the game RNG, so a seed reproduces the sequence
+pub fn random_twist(rng: &mut impl Rng) -> Twist {
+    TWISTS[rng.random_range(0..TWISTS.len())]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{gen_secret, seeded_rng};
+
+    #[test]
+    fn seeded_twists_repeat() {
+        let draw = |seed| (0..8).map(|_| random_twist(&mut seeded_rng(seed))).collect::<Vec<_>>();
+        assert_eq!(draw(11), draw(11));
+        let config = GameConfig { hint_budget: Some(3), ..GameConfig::default() };
+        let twisted = Twist::HintBonanza.apply(&config, (1.0, 100.0)).unwrap();
+        assert_eq!(twisted.hint_budget, Some(6));
+    }
+
+    #[test]
+    fn high_five_needs_a_multiple_of_five_in_range() {
+        let config = GameConfig::default();
+        assert!(Twist::HighFive.apply(&config, (1.0, 4.0)).is_none());
+        let twisted = Twist::HighFive.apply(&config, (1.0, 12.0)).unwrap();
+        for seed in 0..20 {
+            let secret = gen_secret(&mut seeded_rng(seed), 1.0, 12.0, &twisted);
+            assert!(secret == 5.0 || secret == 10.0, "{}", secret);
+        }
+    }
+
+    #[test]
+    fn impossible_multiple_falls_back_to_the_range() {
+        let config = GameConfig { multiple_of: Some(5), ..GameConfig::default() };
+        for seed in 0..20 {
+            let secret = gen_secret(&mut seeded_rng(seed), 1.0, 4.0, &config);
+            assert!((1.0..=4.0).contains(&secret), "{}", secret);
+        }
+    }
+}
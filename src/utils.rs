@@ -5,82 +5,296 @@
 //! - Hint systems
 //! - Game state management
 
-use rand::Rng;
-use rand::seq::IndexedRandom;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
 use colored::Colorize;
+use std::collections::HashMap;
 use std::{f64, io};
+use std::io::{IsTerminal, Write};
 use std::cmp::Ordering;
+use std::thread;
+use std::time::Duration;
+use crate::config::{DiceRange, Distribution, GameConfig, GameMode, GameRange, HintCategory, RepeatGuesses, TimeoutReveal, validate_range};
+use crate::error::GameError;
+use crate::expr;
+use crate::input;
+use crate::round::{RoundState, Verdict};
 
-/// Generates random number between 1.0 and 100.0
-/// Uses thread-local random number generator
-pub fn gen_rand(starting_number: f64, ending_number: f64) -> f64 {
-    rand::rng().random_range(starting_number..=ending_number)
-}
+/// Hints shown together by the bundle hint
+const BUNDLE_SIZE: usize = 2;
+/// Extra draws a bundle slot may make to avoid repeating a shown value
+const BUNDLE_REPICKS: usize = 10;
+/// Draws tried before a decimal secret is left unrounded
+const ROUNDED_DRAWS: usize = 1000;
+/// Rolls tried before a dice secret falls back to a uniform pick
+const DICE_REROLLS: usize = 100;
+/// Extra draws a hard hint may make to find a value small enough to show
+const HARD_REPICKS: usize = 10;
+
+/// A hint table: display template paired with the calculation it describes
+pub type HintTable = Vec<(&'static str, Box<dyn Fn(f64) -> f64>)>;
 
-pub fn game_range_adjuster() -> (f64, f64) {
-    use std::io::{self, Write};
+/// Creates the game's random number generator from a seed
+/// The same seed always yields the same sequence of secrets
+pub fn seeded_rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+/// Generates a random number inside the range
+/// Generates random number between starting_number and ending_number
+/// Uses the game's (seedable) random number generator
+/// Open ends are honored by redrawing the (vanishingly rare) exact bound
+/// With `decimals` set the value is rounded to that many places, redrawing
+/// when rounding pushes it out of the range (a range too narrow to hold
+/// any rounded value eventually gets an unrounded one)
+pub fn gen_rand(rng: &mut impl Rng, range: GameRange, distribution: Distribution, decimals: Option<usize>) -> f64 {
+    let mut draws = 0;
     loop {
-        println!("\nEnter your desired guessing range.");
-        print!("Start (min, >= 1): ");
-        io::stdout().flush().unwrap();
-        let mut start = String::new();
-        io::stdin().read_line(&mut start).expect("Failed to read input");
-
-        print!("End (max, <= 10000): ");
-        io::stdout().flush().unwrap();
-        let mut end = String::new();
-        io::stdin().read_line(&mut end).expect("Failed to read input");
-
-        let start: f64 = match start.trim().parse() {
-            Ok(n) if n >= 1.0 => n,
-            _ => {
-                println!("Invalid start value. Please enter a number >= 1.");
-                continue;
-            }
-        };
-        let end: f64 = match end.trim().parse() {
-            Ok(n) if n > start && n <= 10000.0 => n,
-            _ => {
-                println!("Invalid end value. Please enter a number > start and <= 10000.");
-                continue;
-            }
+        let mut value = match distribution {
+            Distribution::Uniform => rng.random_range(range.start..=range.end),
+            Distribution::Dice(_) => gen_whole(rng, range.start.ceil() as i64, range.end.floor() as i64, distribution) as f64,
+            _ => range.start + draw_unit(rng, distribution) * (range.end - range.start),
         };
-        println!("Range set: {:.1} to {:.1}", start, end);
-        return (start, end);
+        draws += 1;
+        if let Some(places) = decimals.filter(|_| draws <= ROUNDED_DRAWS) {
+            let factor = 10f64.powi(places as i32);
+            value = (value * factor).round() / factor;
+        }
+        if range.contains(value) {
+            return value;
+        }
     }
 }
 
-/// Handles game end scenarios
+/// Draws a position between 0 and 1 shaped by the distribution
+/// Center weighting averages two draws (a triangle peaking at 0.5);
+/// edge weighting shifts that triangle by half so it peaks at both ends
+fn draw_unit(rng: &mut impl Rng, distribution: Distribution) -> f64 {
+    if distribution == Distribution::Uniform {
+        return rng.random();
+    }
+    let triangle = (rng.random::<f64>() + rng.random::<f64>()) / 2.0;
+    match distribution {
+        Distribution::EdgeWeighted => (triangle + 0.5) % 1.0,
+        _ => triangle,
+    }
+}
+
+/// Rolls dice and adds them up
+pub fn roll_dice(rng: &mut impl Rng, dice: DiceRange) -> i64 {
+    (0..dice.count).map(|_| rng.random_range(1..=dice.sides as i64)).sum()
+}
+
+/// Picks a whole number from low to high (inclusive), shaped by the distribution
+/// Dice sums outside the range (after a range change) are re-rolled a few
+/// times before falling back to a uniform pick
+fn gen_whole(rng: &mut impl Rng, low: i64, high: i64, distribution: Distribution) -> i64 {
+    match distribution {
+        Distribution::Uniform => rng.random_range(low..=high),
+        Distribution::Dice(dice) => (0..DICE_REROLLS)
+            .map(|_| roll_dice(rng, dice))
+            .find(|sum| (low..=high).contains(sum))
+            .unwrap_or_else(|| rng.random_range(low..=high)),
+        _ => (low + (draw_unit(rng, distribution) * (high - low + 1) as f64) as i64).min(high),
+    }
+}
+
+/// Generates the secret for a round, honoring the game mode
+/// Integer mode picks a whole number inside the range, and a
+/// `multiple_of` setting restricts it further to multiples of that number
+/// A restriction no number in the range can meet is dropped, so the secret
+/// always stays inside the range
+pub fn gen_secret(rng: &mut impl Rng, starting_number: f64, ending_number: f64, config: &GameConfig) -> f64 {
+    let range = config.game_range(starting_number, ending_number);
+    if let Some(step) = config.multiple_of.filter(|&step| step > 0).map(f64::from)
+        && let Some((low, high)) = range.whole_bounds(step)
+    {
+        return gen_whole(rng, low, high, config.distribution) as f64 * step;
+    }
+    match (config.mode, range.whole_bounds(1.0)) {
+        (GameMode::Integer, Some((low, high))) => gen_whole(rng, low, high, config.distribution) as f64,
+        _ => gen_rand(rng, range, config.distribution, config.secret_decimals),
+    }
+}
+
+/// Prompts the player for a new guessing range until a valid one is entered
 /// Parameters:
-///   is_guess_correct: bool - whether player guessed correctly
-///   attempts: i32 - number of attempts made
+///   max_span: f64 - widest range the player may choose
+///   mode: GameMode - integer mode only accepts whole-number bounds
 /// Returns:
-///   1 to continue, 0 to quit
-pub fn end_situation_handler(is_guess_correct: bool, attempts: i32) -> i32 {
-    // Show appropriate win/lose message
+///   Tuple of (start, end)
+pub fn game_range_adjuster(max_span: f64, mode: GameMode) -> Result<(f64, f64), GameError> {
+    loop {
+        println!("\nEnter your desired guessing range (span up to {}).", max_span);
+        print!("Start (min): ");
+        io::stdout().flush()?;
+        let start = input::normalize_input(&input::read_line()?);
+
+        print!("End (max): ");
+        io::stdout().flush()?;
+        let end = input::normalize_input(&input::read_line()?);
+
+        match parse_range_bounds(&start, &end, max_span, mode) {
+            Ok((start, end)) => {
+                println!("Range set: {:.1} to {:.1}", start, end);
+                return Ok((start, end));
+            },
+            Err(message) => println!("{}", message),
+        }
+    }
+}
+
+/// Parses and checks a pair of typed range bounds
+/// Parameters:
+///   start: &str - lower bound as typed
+///   end: &str - upper bound as typed
+///   max_span: f64 - widest range allowed
+///   mode: GameMode - integer mode only accepts whole-number bounds
+/// Returns:
+///   (start, end), or a message saying what is wrong
+pub fn parse_range_bounds(start: &str, end: &str, max_span: f64, mode: GameMode) -> Result<(f64, f64), String> {
+    let (start, end): (f64, f64) = match (start.parse(), end.parse()) {
+        (Ok(start), Ok(end)) => (start, end),
+        _ => return Err(String::from("Invalid input. Please enter numbers for both bounds.")),
+    };
+    validate_range(start, end, max_span)?;
+    if mode == GameMode::Integer && (start.fract() != 0.0 || end.fract() != 0.0) {
+        return Err(format!("Integer mode needs whole-number bounds (got {} and {}).", start, end));
+    }
+    Ok((start, end))
+}
+
+/// Tolerance that makes a decimal secret winnable: 1% of the range
+pub fn winnable_tolerance(range: (f64, f64)) -> f64 {
+    (range.1 - range.0).abs() / 100.0
+}
+
+/// Gives an exact decimal game some slack, since its secret can never be
+/// hit (unless the secret is rounded to a few places)
+/// Used wherever a round is set up, so every path plays the same game
+/// Returns:
+///   Whether the tolerance was changed
+pub fn make_winnable(config: &mut GameConfig, range: (f64, f64)) -> bool {
+    if config.mode != GameMode::Decimal || config.tolerance != 0.0 || config.secret_decimals.is_some() {
+        return false;
+    }
+    config.tolerance = winnable_tolerance(range);
+    true
+}
+
+/// Prints the win/lose message for a finished round
+/// Parameters:
+///   is_guess_correct: bool - whether player guessed correctly
+///   attempts: u32 - number of attempts made
+pub fn end_of_round_message(is_guess_correct: bool, attempts: u32) {
     if is_guess_correct {
         println!("{}", format!("You won in {} attempts!", attempts).green().bold());
     } else {
         println!("{}", format!("Unfortunately, you lost on the {} attempt(s).", attempts).red().bold());
     }
+}
+
+/// Handles game end scenarios
+/// Parameters:
+///   is_guess_correct: bool - whether player guessed correctly
+///   round: &RoundState - attempts made and hints taken
+///   bell: bool - ring the terminal bell on a win
+/// Returns:
+///   1 to continue, 0 to quit
+pub fn end_situation_handler(is_guess_correct: bool, round: &RoundState, bell: bool) -> Result<i32, GameError> {
+    // Show appropriate win/lose message
+    end_of_round_message(is_guess_correct, round.attempts);
+    println!("{}", round.hint_summary());
+    print!("{}", win_bell(is_guess_correct, bell, io::stdout().is_terminal()));
     
     // Prompt for next action
     println!("\nWould you like to play again?");
     println!("1 = Yes, 0 = No: ");
     
-    let mut choice = String::new();
-    io::stdin().read_line(&mut choice).expect("Failed to read input");
+    let choice = input::normalize_input(&input::read_line()?);
     
     // Parse input, default to quit on error
-    choice.trim().parse().unwrap_or(0)
+    Ok(choice.parse().unwrap_or(0))
+}
+
+/// The terminal bell (BEL) for a win, when asked for and heard by a terminal
+/// Parameters:
+///   won: bool - whether the round was won
+///   bell: bool - whether the bell is switched on
+///   terminal: bool - whether the output is a terminal
+fn win_bell(won: bool, bell: bool, terminal: bool) -> &'static str {
+    if won && bell && terminal { "\x07" } else { "" }
+}
+
+/// Hints drawn without replacement: every hint in a table is shown once
+/// before any repeats, in an order fixed by the seed
+pub struct HintDeck {
+    rng: StdRng,
+    /// Undrawn hint indices per table
+    piles: HashMap<HintCategory, Vec<usize>>,
+}
+
+impl HintDeck {
+    /// Creates an empty deck; piles are shuffled on first draw
+    /// Parameters:
+    ///   seed: u64 - fixes the shuffle order (take it from the game RNG)
+    pub fn new(seed: u64) -> Self {
+        HintDeck { rng: seeded_rng(seed), piles: HashMap::new() }
+    }
+
+    /// Draws the next hint index from a table, reshuffling when it runs out
+    /// Parameters:
+    ///   category: HintCategory - which table is being drawn from
+    ///   pool_size: usize - number of hints in that table
+    pub fn draw(&mut self, category: HintCategory, pool_size: usize) -> usize {
+        let pile = self.piles.entry(category).or_default();
+        if pile.is_empty() {
+            pile.extend(0..pool_size);
+            pile.shuffle(&mut self.rng);
+        }
+        pile.pop().unwrap_or(0)
+    }
+}
+
+/// Picks which hint of a table to show
+/// Uses the round's deck when there is one, otherwise any hint at random
+fn pick_hint(deck: Option<&mut HintDeck>, category: HintCategory, pool_size: usize) -> usize {
+    match deck {
+        Some(deck) => deck.draw(category, pool_size),
+        None => rand::rng().random_range(0..pool_size),
+    }
 }
 
 /// Provides easy hints using simple arithmetic
 /// Parameters:
 ///   secret_number: f64 - the target number to hint toward
-fn easy_hint_chooser(secret_number: f64) {
+///   deck: Option<&mut HintDeck> - draw without replacement when set
+///   scale: f64 - multiplier applied to the value before display
+/// Returns:
+///   The hint's display line
+fn easy_hint_chooser(secret_number: f64, deck: Option<&mut HintDeck>, scale: f64) -> String {
+    let expressions = easy_hints();
+
+    // Randomly select one hint
+    let (hint, expr) = &expressions[pick_hint(deck, HintCategory::Easy, expressions.len())];
+    let calculated_value = expr(secret_number) * scale;
+    let formatted_hint = hint.replace("{:.1}", &format!("{:.1}", calculated_value));
+    format!("{}: {} = {:.2}{}", "Easy Hint".blue(), formatted_hint, calculated_value, scale_note(scale))
+}
+
+/// Marks a hint whose value was scaled for display, e.g. " (scaled ×10)"
+fn scale_note(scale: f64) -> String {
+    if scale == 1.0 {
+        String::new()
+    } else {
+        format!(" (scaled ×{})", format_number(scale))
+    }
+}
+
+/// Every easy hint, also used by hint bundles
+fn easy_hints() -> HintTable {
     // Collection of possible hint formats and their calculations
-    let expressions: Vec<(&str, Box<dyn Fn(f64) -> f64>)> =  vec![
+    vec![
     //every tuples format:
     //(hint(string), Box::new(|x| (the actual calculation logic))),
     // Each tuple: (hint string, closure to compute value)
@@ -230,20 +444,89 @@ fn easy_hint_chooser(secret_number: f64) {
             "The secret number is 16 negative steps from the number S - 8",
             Box::new(|x| x + 16.0 + 8.0), // Simplified formula: N = S - 24
         )
-    ];
-    
-    // Randomly select and display one hint
-    let mut rng = rand::rng();
-    let (hint, expr) = expressions.choose(&mut rng).unwrap();
-    let calculated_value = expr(secret_number);
-    let formatted_hint = hint.replace("{:.1}", &format!("{:.1}", calculated_value));
-    println!("{}: {} = {:.2}", "Easy Hint".blue(), formatted_hint, calculated_value);
-} 
+    ]
+}
+
+/// Picks easy hints for a bundle, avoiding repeated displayed values
+/// Each slot re-picks a bounded number of times to find a value not shown
+/// yet; if none turns up, the last pick is kept and the duplicate is noted
+/// Parameters:
+///   secret_number: f64 - the target number to hint toward
+///   deck: Option<&mut HintDeck> - draw without replacement when set
+///   size: usize - how many hints the bundle holds
+///   scale: f64 - multiplier applied to each value before display
+/// Returns:
+///   Display lines, one per hint, labelled so no two look the same
+pub fn bundle_hints(secret_number: f64, mut deck: Option<&mut HintDeck>, size: usize, scale: f64) -> Vec<String> {
+    let expressions = easy_hints();
+    let mut picked: Vec<(usize, String)> = Vec::new();
+    for _ in 0..size {
+        let mut choice = None;
+        for _ in 0..=BUNDLE_REPICKS {
+            let index = pick_hint(deck.as_deref_mut(), HintCategory::Easy, expressions.len());
+            let shown = format!("{:.2}", expressions[index].1(secret_number) * scale);
+            let fresh = picked.iter().all(|(other, value)| *other != index && *value != shown);
+            choice = Some((index, shown));
+            if fresh {
+                break;
+            }
+        }
+        picked.extend(choice);
+    }
+
+    picked
+        .iter()
+        .enumerate()
+        .map(|(i, (index, shown))| {
+            let value = expressions[*index].1(secret_number) * scale;
+            let formatted_hint = expressions[*index].0.replace("{:.1}", &format!("{:.1}", value));
+            let label = format!("Bundle Hint {}", i + 1).blue();
+            let mut line = format!("{}: {} = {}{}", label, formatted_hint, shown, scale_note(scale));
+            if let Some(first) = picked[..i].iter().position(|(_, other)| other == shown) {
+                line.push_str(&format!(" (same value as hint {}, by another route)", first + 1));
+            }
+            line
+        })
+        .collect()
+}
 
 /// Provides complex mathematical hints
 /// Parameters:
 ///   secret_number: f64 - the target number to hint toward
-fn hard_hint_chooser(secret_number: f64) {
+///   deck: Option<&mut HintDeck> - draw without replacement when set
+///   explain: bool - also show how to solve the equation for S
+///   scale: f64 - multiplier applied to the value before display
+///   max_value: f64 - largest value worth showing; falls back to an easy
+///     hint when no equation stays under it
+/// Returns:
+///   Display lines: the hint, then the explanation when asked for
+fn hard_hint_chooser(secret_number: f64, mut deck: Option<&mut HintDeck>, explain: bool, scale: f64, max_value: f64) -> Vec<String> {
+    let expressions = hard_hints();
+
+    // Randomly select one hint, re-picking values too big to read
+    let tame = (0..=HARD_REPICKS)
+        .map(|_| pick_hint(deck.as_deref_mut(), HintCategory::Hard, expressions.len()))
+        .find(|&index| (expressions[index].1(secret_number) * scale).abs() <= max_value);
+    let Some(index) = tame else {
+        return vec![
+            format!("{}", "Every equation came out too big to read - here's an easy hint instead.".purple()),
+            easy_hint_chooser(secret_number, deck, scale),
+        ];
+    };
+    let (hint, expr) = &expressions[index];
+    let mut lines = vec![format!("{}: {} = {:.2}{}", "Hard Hint".purple(), hint, expr(secret_number) * scale, scale_note(scale))];
+    if explain {
+        let mut explanation = explain_hint(expr.as_ref());
+        if scale != 1.0 {
+            explanation.push_str(&format!(" (N is the shown value divided by {})", format_number(scale)));
+        }
+        lines.push(format!("{}: {}", "Solving".purple(), explanation));
+    }
+    lines
+}
+
+/// Every hard hint equation, also used by the hint-math trainer
+pub fn hard_hints() -> HintTable {
       // Each tuple: (hint string, closure to compute value)
     /*
      * Define a vector of tuples containing hard hint strings and their corresponding calculations
@@ -252,7 +535,7 @@ fn hard_hint_chooser(secret_number: f64) {
      * - A closure that computes the hint value based on the secret number
      */
     // Collection of complex equation hints
-    let expressions: Vec<(&str, Box<dyn Fn(f64) -> f64>)> = vec![
+    vec![
         (
             "(S^2 - 3)×4 + (S^3÷2 - 7) = {}",
             Box::new(|x| (x.powi(2) - 3.0) * 4.0 + (x.powi(3) / 2.0 - 7.0)), // Simplified formula: N = 4(S² - 3) + (S³ / 2) - 7
@@ -487,71 +770,1408 @@ fn hard_hint_chooser(secret_number: f64) {
         // Simple fractions
         ("S/2 + 3 = {}", Box::new(|x| x / 2.0 + 3.0)), // Simplified formula: N = S/2 + 3
         ("(S + 1)/3 = {}", Box::new(|x| (x + 1.0) / 3.0)) // Simplified formula: N = (S + 1) / 3
+    ]
+}
+
+/// Describes how to get back from a hint's value N to the secret S
+/// Straight-line formulas are inverted exactly; anything else needs
+/// solving numerically
+fn explain_hint(expr: &dyn Fn(f64) -> f64) -> String {
+    let Some((slope, intercept)) = affine_form(expr) else {
+        return String::from("No neat rearrangement - solve numerically (try values of S until both sides match).");
+    };
+    let offset = |value: f64| match value {
+        0.0 => String::new(),
+        v if v > 0.0 => format!(" + {}", format_number(v)),
+        v => format!(" - {}", format_number(-v)),
+    };
+    if slope.fract() == 0.0 {
+        // S = (N - b) ÷ a, keeping the whole-number slope as a divisor
+        let shifted = format!("N{}", offset(-intercept));
+        match slope {
+            1.0 => format!("S = {}", shifted),
+            _ => format!("S = ({}) ÷ {}", shifted, format_number(slope)),
+        }
+    } else {
+        // S = N/a - b/a, for fractional slopes such as S/2 + 3
+        let round = |value: f64| (value * 1e9).round() / 1e9;
+        format!("S = {}N{}", format_number(round(1.0 / slope)), offset(round(-intercept / slope)))
+    }
+}
+
+/// Slope and intercept of a formula that is a straight line in S
+/// Checked by sampling a few points, so it also spots formulas that only
+/// look non-linear, like (S² + 5S + 6)÷(S + 2)
+fn affine_form(expr: &dyn Fn(f64) -> f64) -> Option<(f64, f64)> {
+    let intercept = expr(0.0);
+    let slope = expr(1.0) - intercept;
+    let straight = [2.0, -3.0, 10.0, 0.5]
+        .iter()
+        .all(|&x| (expr(x) - (slope * x + intercept)).abs() < 1e-9);
+    (straight && slope != 0.0).then_some((slope, intercept))
+}
+
+/// Number of guesses a perfect (bisecting) player still needs
+/// Parameters:
+///   span: f64 - size of the feasible interval (candidate count in integer mode)
+/// Returns:
+///   None when the span is 1 or less, i.e. the answer is already known
+pub fn perfect_play_guesses(span: f64) -> Option<u32> {
+    if span <= 1.0 {
+        None
+    } else {
+        Some(span.log2().ceil() as u32)
+    }
+}
+
+/// Most guesses the auto-solver makes before giving up on a decimal secret
+const AUTO_SOLVE_LIMIT: usize = 200;
+
+/// Finds the secret by bisection, the way a perfect player would
+/// Parameters:
+///   secret: f64 - the number to find
+///   range: (f64, f64) - guessing range
+///   config: &GameConfig - game mode and win tolerance
+/// Returns:
+///   Every guess made, the last one being the hit (or the last try when
+///   a decimal secret could not be pinned down)
+pub fn auto_solve(secret: f64, range: (f64, f64), config: &GameConfig) -> Vec<f64> {
+    auto_solve_steps(secret, range, config).into_iter().map(|step| step.guess).collect()
+}
+
+/// One bisection step: the interval still open and the guess made in it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SolveStep {
+    pub low: f64,
+    pub high: f64,
+    pub guess: f64,
+}
+
+/// Same search as `auto_solve`, keeping the interval before each guess
+pub fn auto_solve_steps(secret: f64, range: (f64, f64), config: &GameConfig) -> Vec<SolveStep> {
+    let (mut low, mut high) = range;
+    let mut steps = Vec::new();
+    while steps.len() < AUTO_SOLVE_LIMIT {
+        let guess = match config.mode {
+            GameMode::Integer => ((low + high) / 2.0).floor(),
+            GameMode::Decimal => (low + high) / 2.0,
+        };
+        steps.push(SolveStep { low, high, guess });
+        if (guess - secret).abs() <= config.tolerance {
+            break;
+        }
+        let step = if config.mode == GameMode::Integer { 1.0 } else { 0.0 };
+        if guess < secret {
+            low = guess + step;
+        } else {
+            high = guess - step;
+        }
+    }
+    steps
+}
+
+/// Explains each solver step: why that guess, and what the answer showed
+/// The reaction to a miss is read off the next step's interval
+/// Parameters:
+///   steps: &[SolveStep] - the solver's steps, as from `auto_solve_steps`
+///   secret: f64 - the number being solved for
+///   tolerance: f64 - how close counts as correct
+/// Returns:
+///   One (reasoning, reaction) pair per step
+pub fn narrate_steps(steps: &[SolveStep], secret: f64, tolerance: f64) -> Vec<(String, String)> {
+    steps
+        .iter()
+        .enumerate()
+        .map(|(i, step)| {
+            let reasoning = format!(
+                "The range is {}–{}, so I'll guess the midpoint {} to halve it.",
+                format_number(step.low),
+                format_number(step.high),
+                format_number(step.guess)
+            );
+            let verdict = if step.guess < secret { "Too small" } else { "Too big" };
+            let reaction = match steps.get(i + 1) {
+                _ if (step.guess - secret).abs() <= tolerance => String::from("Correct → solved!"),
+                Some(next) => format!("{} → the answer is {}–{}", verdict, format_number(next.low), format_number(next.high)),
+                None => format!("{} → out of guesses", verdict),
+            };
+            (reasoning, reaction)
+        })
+        .collect()
+}
+
+/// Size of the interval still feasible given what the player knows
+/// Integer mode counts the whole numbers left; decimal mode uses the width
+pub fn feasible_span(round: &RoundState, range: (f64, f64), mode: GameMode) -> f64 {
+    let (low, high) = round.known_bounds(range);
+    match mode {
+        GameMode::Integer => {
+            let first = if round.known_low.is_some() { low.floor() + 1.0 } else { low.ceil() };
+            let last = if round.known_high.is_some() { high.ceil() - 1.0 } else { high.floor() };
+            (last - first + 1.0).max(0.0)
+        },
+        GameMode::Decimal => high - low,
+    }
+}
+
+/// Hints the player can ask for before guessing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HintChoice {
+    Easy,
+    Hard,
+    NoHint,
+    PerfectPlay,
+    Quartile,
+    Suggest,
+    Position,
+    Estimate,
+    Bundle,
+    /// Where the secret sits within the bounds already narrowed to
+    Focus,
+    /// Show an example of each hint category without spending anything
+    Preview,
+}
+
+impl HintChoice {
+    /// Every hint in menu order
+    pub const MENU: [HintChoice; 11] = [
+        HintChoice::Easy,
+        HintChoice::Hard,
+        HintChoice::NoHint,
+        HintChoice::PerfectPlay,
+        HintChoice::Quartile,
+        HintChoice::Suggest,
+        HintChoice::Position,
+        HintChoice::Estimate,
+        HintChoice::Bundle,
+        HintChoice::Focus,
+        HintChoice::Preview,
     ];
-    
-    // Randomly select and display one hint
-    let mut rng = rand::rng();
-    let (hint, expr) = expressions.choose(&mut rng).unwrap();
-    println!("{}: {} = {:.2}", "Hard Hint".purple(), hint, expr(secret_number));
+
+    /// Number the player types to pick this hint
+    pub fn key(&self) -> &'static str {
+        match self {
+            HintChoice::Easy => "1",
+            HintChoice::Hard => "2",
+            HintChoice::NoHint => "3",
+            HintChoice::PerfectPlay => "4",
+            HintChoice::Quartile => "5",
+            HintChoice::Suggest => "6",
+            HintChoice::Position => "7",
+            HintChoice::Estimate => "8",
+            HintChoice::Bundle => "9",
+            HintChoice::Focus => "f",
+            HintChoice::Preview => "p",
+        }
+    }
+
+    /// What the hint is, as shown in the menu
+    pub fn label(&self) -> &'static str {
+        match self {
+            HintChoice::Easy => "Easy hint",
+            HintChoice::Hard => "Hard hint",
+            HintChoice::NoHint => "No hints (I'm feeling lucky!)",
+            HintChoice::PerfectPlay => "Perfect-play hint - how many guesses you still need",
+            HintChoice::Quartile => "Quartile hint - which quarter of the range",
+            HintChoice::Suggest => "Suggest my next guess - from my own guesses only",
+            HintChoice::Position => "Position hint - how far through the range",
+            HintChoice::Estimate => "Estimate hint - a rough guess with a margin",
+            HintChoice::Bundle => "Hint bundle - two easy hints at once",
+            HintChoice::Focus => "Focus hint - which third of what your guesses narrowed it to",
+            HintChoice::Preview => "Preview hints - an example of each kind, free",
+        }
+    }
+
+    /// Menu line with the hint's power and cost, e.g. "1. Easy hint (reveals ~1 easy equation, cost 1)"
+    pub fn menu_line(&self) -> String {
+        match self.category() {
+            Some(category) => format!("{}. {} ({}, cost {})", self.key(), self.label(), category.power(), self.cost()),
+            None => format!("{}. {}", self.key(), self.label()),
+        }
+    }
+
+    /// Maps a menu entry to a hint (anything unrecognized means no hint)
+    pub fn from_input(input: &str) -> HintChoice {
+        let input = input.trim();
+        HintChoice::MENU.into_iter().find(|choice| choice.key().eq_ignore_ascii_case(input)).unwrap_or(HintChoice::NoHint)
+    }
+
+    /// Allowance category this hint draws from (None for no hint)
+    pub fn category(&self) -> Option<HintCategory> {
+        match self {
+            HintChoice::Easy | HintChoice::Bundle => Some(HintCategory::Easy),
+            HintChoice::Hard => Some(HintCategory::Hard),
+            HintChoice::PerfectPlay
+            | HintChoice::Quartile
+            | HintChoice::Suggest
+            | HintChoice::Position
+            | HintChoice::Estimate
+            | HintChoice::Focus => {
+                Some(HintCategory::Proximity)
+            },
+            HintChoice::NoHint | HintChoice::Preview => None,
+        }
+    }
+
+    /// How much of the hint budget this hint uses
+    pub fn cost(&self) -> u32 {
+        match self {
+            HintChoice::NoHint | HintChoice::Preview => 0,
+            HintChoice::Easy | HintChoice::Hard | HintChoice::Suggest | HintChoice::Position | HintChoice::Focus => 1,
+            HintChoice::Quartile | HintChoice::Estimate | HintChoice::Bundle => 2,
+            // An exact count of guesses left is the premium hint
+            HintChoice::PerfectPlay => 3,
+        }
+    }
+}
+
+/// Finds which quarter of the range holds the secret
+/// A secret exactly on a boundary belongs to the upper quarter
+/// (the range end itself stays in the fourth)
+/// Parameters:
+///   secret: f64 - the secret number
+///   range: (f64, f64) - current guessing range
+/// Returns:
+///   The quarter (1-4) and its bounds
+pub fn quartile(secret: f64, range: (f64, f64)) -> (u32, f64, f64) {
+    let (start, end) = range;
+    let width = (end - start) / 4.0;
+    let quarter = (((secret - start) / width).floor() as i64).clamp(0, 3) as u32;
+    let low = start + width * quarter as f64;
+    let high = if quarter == 3 { end } else { low + width };
+    (quarter + 1, low, high)
+}
+
+/// Ordinal name of a quarter (1-4), e.g. "second"
+fn quarter_name(quarter: u32) -> &'static str {
+    ["first", "second", "third", "fourth"][quarter.clamp(1, 4) as usize - 1]
+}
+
+/// What a timed-out round shows about its secret
+/// Parameters:
+///   secret: f64 - the secret nobody found in time
+///   range: (f64, f64) - current guessing range
+///   reveal: TimeoutReveal - how much to give away
+/// Returns:
+///   The line to print, or None when nothing is revealed
+pub fn timeout_reveal_line(secret: f64, range: (f64, f64), reveal: TimeoutReveal) -> Option<String> {
+    match reveal {
+        TimeoutReveal::Nothing => None,
+        TimeoutReveal::Quartile => {
+            let (quarter, low, high) = quartile(secret, range);
+            Some(format!(
+                "It was in the {} quarter: between {} and {}.",
+                quarter_name(quarter),
+                format_number(low),
+                format_number(high)
+            ))
+        },
+        TimeoutReveal::Secret => Some(format!("The number was {:.2}.", secret)),
+    }
+}
+
+/// How a surrendered secret could have been found
+/// Parameters:
+///   secret: f64 - the revealed secret
+///   range: (f64, f64) - current guessing range
+///   config: &GameConfig - game mode and win tolerance
+/// Returns:
+///   Lines covering the quarter, parity (integer mode) and an optimal start
+pub fn reveal_breakdown(secret: f64, range: (f64, f64), config: &GameConfig) -> Vec<String> {
+    let (quarter, low, high) = quartile(secret, range);
+    let mut lines = vec![format!(
+        "It was in the {} quarter ({}–{}).",
+        quarter_name(quarter),
+        format_number(low),
+        format_number(high)
+    )];
+    if config.mode == GameMode::Integer {
+        let parity = if secret.rem_euclid(2.0) == 0.0 { "even" } else { "odd" };
+        lines.push(format!("It was {}.", parity));
+    }
+    let solve = auto_solve(secret, range, config);
+    if let Some(first) = solve.first() {
+        lines.push(format!(
+            "An optimal first guess was {}; halving from there finds it in {} guess(es).",
+            format_number(*first),
+            solve.len()
+        ));
+    }
+    lines
+}
+
+/// How far through the range the secret sits, as a rounded percentage
+/// A zero-width range puts the secret at 0%
+pub fn position_pct(secret: f64, range: (f64, f64)) -> u32 {
+    let (start, end) = range;
+    if end <= start {
+        return 0;
+    }
+    ((secret - start) / (end - start) * 100.0).clamp(0.0, 100.0).round() as u32
+}
+
+/// Which third of the narrowed-down interval holds the secret
+/// A secret exactly on a boundary belongs to the upper third
+/// Parameters:
+///   secret: f64 - the secret number
+///   bounds: (f64, f64) - the bounds the player's guesses have narrowed to
+/// Returns:
+///   "lower", "middle" or "upper"
+pub fn focus_third(secret: f64, bounds: (f64, f64)) -> &'static str {
+    let (low, high) = bounds;
+    if high <= low {
+        return "middle";
+    }
+    match ((secret - low) / (high - low) * 3.0).floor() as i64 {
+        ..=0 => "lower",
+        1 => "middle",
+        _ => "upper",
+    }
+}
+
+/// A noisy guess at the secret with a fixed margin of error
+/// The margin is a tenth of the range and the noise never exceeds it,
+/// so the stated interval always contains the secret
+/// Parameters:
+///   rng: &mut impl Rng - the game's random number generator
+///   secret: f64 - the secret number
+///   range: (f64, f64) - current guessing range
+///   mode: GameMode - integer mode keeps the estimate and margin whole
+/// Returns:
+///   (estimate, margin)
+pub fn estimate(rng: &mut impl Rng, secret: f64, range: (f64, f64), mode: GameMode) -> (f64, f64) {
+    let margin = (range.1 - range.0).abs() / 10.0;
+    match mode {
+        GameMode::Integer => {
+            let margin = margin.ceil().max(1.0);
+            let noise = rng.random_range(-(margin as i64)..=margin as i64);
+            (secret + noise as f64, margin)
+        },
+        GameMode::Decimal => (secret + rng.random_range(-margin..=margin), margin),
+    }
+}
+
+/// Best next guess from what the player's own guesses have established
+/// Only the known bounds are used, never the secret
+/// Parameters:
+///   round: &RoundState - bounds set by earlier guesses
+///   range: (f64, f64) - full guessing range
+///   mode: GameMode - integer mode suggests a whole number
+/// Returns:
+///   (known low, known high, suggested guess)
+pub fn suggest_guess(round: &RoundState, range: (f64, f64), mode: GameMode) -> (f64, f64, f64) {
+    let (low, high) = round.known_bounds(range);
+    let midpoint = (low + high) / 2.0;
+    let guess = match mode {
+        GameMode::Integer => midpoint.floor(),
+        GameMode::Decimal => midpoint,
+    };
+    (low, high, guess)
 }
 
 /// Displays hint based on player's choice
 /// Parameters:
-///   op: &str - player's hint selection ("1", "2", or other)
+///   choice: HintChoice - player's hint selection
 ///   secret_number: f64 - number to generate hints for
-pub fn choose_hint(op: &str, secret_number: f64) {
-    match op.trim() {
-        "1" => {
+///   round: &mut RoundState - what the player already knows, and hints used
+///   range: (f64, f64) - current guessing range
+///   config: &GameConfig - game mode and hint budget
+///   rng: &mut impl Rng - the game's random number generator, for noisy hints
+pub fn choose_hint(
+    choice: HintChoice,
+    secret_number: f64,
+    round: &mut RoundState,
+    range: (f64, f64),
+    config: &GameConfig,
+    rng: &mut impl Rng,
+) {
+    if choice == HintChoice::Focus && round.trace().is_empty() {
+        println!("{}", "The focus hint needs at least one guess to narrow from.".red());
+        return;
+    }
+    if choice.category().is_some() {
+        if config.blind {
+            println!("{}", "Hints are off in blind mode.".red());
+            return;
+        }
+        if config.hint_budget.is_some_and(|budget| round.hints_used + choice.cost() > budget) {
+            println!("{}", "No hints left this round!".red());
+            return;
+        }
+        if let Some(category) = choice.category() {
+            let limit = config.hint_limits.get(&category).copied();
+            if let Some(left) = round.hints_remaining(category, limit) {
+                if left == 0 {
+                    println!("{}", format!("No {} hints left this round!", category.name()).red());
+                    return;
+                }
+                round.hints_left.insert(category, left - 1);
+            }
+        }
+        round.hints_used += choice.cost();
+        if let Some(category) = choice.category() {
+            *round.hints_by_category.entry(category).or_default() += 1;
+        }
+    }
+
+    match choice {
+        HintChoice::Easy => {
             println!("{}", "Easy hint selected!".blue());
-            easy_hint_chooser(secret_number);
+            println!("{}", easy_hint_chooser(secret_number, round.hint_deck.as_mut(), config.hint_scale));
         },
-        "2" => {
+        HintChoice::Bundle => {
+            println!("{}", "Hint bundle selected!".blue());
+            for line in bundle_hints(secret_number, round.hint_deck.as_mut(), BUNDLE_SIZE, config.hint_scale) {
+                println!("{}", line);
+            }
+        },
+        HintChoice::Hard => {
             println!("{}", "Hard hint selected! Calculator recommended.".purple());
-            hard_hint_chooser(secret_number);
+            for line in hard_hint_chooser(secret_number, round.hint_deck.as_mut(), config.explain_hints, config.hint_scale, config.max_hint_value) {
+                println!("{}", line);
+            }
+        },
+        HintChoice::PerfectPlay => {
+            println!("{}", "Perfect-play hint selected!".cyan());
+            match perfect_play_guesses(feasible_span(round, range, config.mode)) {
+                Some(guesses) => println!("{}: A perfect player needs {} more guess(es) from here.", "Perfect-Play Hint".cyan(), guesses),
+                None => println!("{}: You already know the answer.", "Perfect-Play Hint".cyan()),
+            }
+        },
+        HintChoice::Quartile => {
+            println!("{}", "Quartile hint selected!".green());
+            let (quarter, low, high) = quartile(secret_number, range);
+            let ordinal = quarter_name(quarter);
+            println!(
+                "{}: It's in the {} quarter: between {} and {}",
+                "Quartile Hint".green(),
+                ordinal,
+                format_number(low),
+                format_number(high)
+            );
+        },
+        HintChoice::Suggest => {
+            println!("{}", "Guess suggestion selected!".cyan());
+            let (low, high, guess) = suggest_guess(round, range, config.mode);
+            println!(
+                "{}: Halving {}–{} → try {}",
+                "Suggestion".cyan(),
+                format_number(low),
+                format_number(high),
+                format_number(guess)
+            );
+        },
+        HintChoice::Position => {
+            println!("{}", "Position hint selected!".green());
+            println!(
+                "{}: The number is about {}% of the way through the range.",
+                "Position Hint".green(),
+                position_pct(secret_number, range)
+            );
+        },
+        HintChoice::Estimate => {
+            println!("{}", "Estimate hint selected!".green());
+            let (center, margin) = estimate(rng, secret_number, range, config.mode);
+            println!(
+                "{}: I think it's around {} (give or take {})",
+                "Estimate Hint".green(),
+                format_number(center),
+                format_number(margin)
+            );
         },
-        _ => println!("{}", "No hints - good luck!".yellow()),
+        HintChoice::Focus => {
+            println!("{}", "Focus hint selected!".cyan());
+            let (low, high) = round.known_bounds(range);
+            println!(
+                "{}: Within the {}–{} you've narrowed to, the number is in the {} third.",
+                "Focus Hint".cyan(),
+                format_number(low),
+                format_number(high),
+                focus_third(secret_number, (low, high))
+            );
+        },
+        HintChoice::NoHint => println!("{}", "No hints - good luck!".yellow()),
+        HintChoice::Preview => {
+            for line in hint_previews(range, config.hint_scale) {
+                println!("{}", line);
+            }
+        },
+    }
+}
+
+/// Every easy and hard hint template with the value it gives for a secret
+/// Non-finite values (a broken formula) are flagged in red
+/// Parameters:
+///   secret: f64 - value to work each hint out for
+/// Returns:
+///   Lines grouped by category, each group headed by its template count
+pub fn dump_hints(secret: f64) -> Vec<String> {
+    let mut lines = Vec::new();
+    for (name, table) in [("Easy", easy_hints()), ("Hard", hard_hints())] {
+        lines.push(format!("{} hints ({}):", name, table.len()));
+        for (hint, expr) in &table {
+            let value = expr(secret);
+            let line = format!("  {} -> {}", hint.trim(), value);
+            lines.push(if value.is_finite() { line } else { format!("{} {}", line.red(), "(non-finite)".red().bold()) });
+        }
     }
+    lines
+}
+
+/// Placeholder secret for hint previews (pulled into the range when outside it)
+const PREVIEW_SECRET: f64 = 42.0;
+
+/// One example hint per category, worked out for a placeholder secret
+/// Uses the first entry of each hint table so the examples never change
+/// Parameters:
+///   range: (f64, f64) - current guessing range
+///   scale: f64 - multiplier applied to easy and hard hint values
+/// Returns:
+///   A heading line followed by one line per category
+pub fn hint_previews(range: (f64, f64), scale: f64) -> Vec<String> {
+    let secret = PREVIEW_SECRET.clamp(range.0, range.1);
+    let easy = &easy_hints()[0];
+    let hard = &hard_hints()[0];
+    let (quarter, low, high) = quartile(secret, range);
+    let value = (easy.1)(secret) * scale;
+    vec![
+        format!("{}", format!("EXAMPLE — not the real number (pretend it is {})", format_number(secret)).yellow().bold()),
+        format!("{}: {} = {:.2}{}", "Easy Hint".blue(), easy.0.replace("{:.1}", &format!("{:.1}", value)), value, scale_note(scale)),
+        format!("{}: {} = {:.2}{}", "Hard Hint".purple(), hard.0, (hard.1)(secret) * scale, scale_note(scale)),
+        format!(
+            "{}: It's in the {} quarter: between {} and {}",
+            "Quartile Hint".green(),
+            quarter_name(quarter),
+            format_number(low),
+            format_number(high)
+        ),
+    ]
 }
 
 /// Manages the core guessing loop
 /// Parameters:
-///   op: &str - hint choice
 ///   secret: f64 - target number
-///   attempts: i32 - current attempt count
+///   round: &mut RoundState - attempts and per-round tracking, updated in place
+///   range: (f64, f64) - inclusive bounds accepted as guesses
+///   config: &GameConfig - optional feedback settings
 /// Returns:
-///   Tuple of (success: bool, total_attempts: i32)
-pub fn game_loop(secret: f64, mut attempts: i32) -> (bool, i32) {
+///   Whether the guess was correct
+pub fn game_loop(secret: f64, round: &mut RoundState, range: (f64, f64), config: &GameConfig) -> Result<bool, GameError> {
+    let (start, end) = range;
     loop {
-        attempts += 1;
-        println!("\nAttempt #{}", attempts);
+        // Runaway sessions (e.g. a script that never finds the number) stop here
+        if round.attempts >= config.attempt_ceiling {
+            return Err(GameError::AttemptCeiling(config.attempt_ceiling));
+        }
+        if let Some(header) = attempt_header(round.attempts + 1, config) {
+            println!("{}", header);
+        }
         
+        if let Some(budget) = config.point_budget {
+            println!("Points left: {}", budget.saturating_sub(round.points_spent));
+        }
+
         // Get and validate player's guess
-        print!("Enter your guess (1-100): ");
-        let mut guess = String::new();
-        io::stdin().read_line(&mut guess).expect("Failed to read input");
+        print!("{}", guess_prompt(config, start, end));
+        io::stdout().flush()?;
+        let guess = input::normalize_input(&input::read_line()?);
+        // A malformed expression is a typo, not a guess, so it costs no attempt
+        // Reviewing earlier guesses is free
+        if guess.eq_ignore_ascii_case("sorted") {
+            for line in guess_usefulness_lines(round, range, config) {
+                println!("{}", line);
+            }
+            continue;
+        }
+        let guess = if config.expr_input {
+            match expr::evaluate(&guess) {
+                Ok(value) => Some(value),
+                Err(message) => {
+                    println!("{}", format!("Couldn't work that out: {}", message).red());
+                    continue;
+                }
+            }
+        } else {
+            guess.parse().ok()
+        };
+        // A repeated guess is likely a slip, so check before it costs an attempt
+        if let Some(value) = guess
+            && let Some(earlier) = round.repeat_of(value)
+            && !confirm_repeat(value, earlier.verdict, config)?
+        {
+            continue;
+        }
+        round.attempts = round.attempts.saturating_add(1);
+        round.total_attempts = round.total_attempts.saturating_add(1);
+        round.game_attempts = round.game_attempts.saturating_add(1);
+
+        // A guess after the time limit no longer counts
+        if config.time_limit.is_some_and(|limit| round.started.elapsed() > limit) {
+            round.timed_out = true;
+            println!("{}", "Time's up!".red().bold());
+            return Ok(false);
+        }
         
-        let guess: f64 = match guess.trim().parse() {
-            Ok(num) if (1.0..=100.0).contains(&num) => num,
+        let guess: f64 = match guess {
+            Some(num) if config.game_range(start, end).contains(num) => num,
             _ => {
-                println!("{}", "Invalid input. Please enter 1-100".red());
+                println!("{}", format!("Invalid input. Please enter {}", config.game_range(start, end)).red());
                 continue;
             }
         };
-        
+
+        // Point out guesses the player's own earlier guesses rule out
+        if let Some(nudge) = bounds_nudge(round, guess, config) {
+            println!("{}", nudge.yellow());
+        }
+
         // Compare guess to secret number
-        match guess.partial_cmp(&secret).unwrap() {
-            Ordering::Less => {
-                println!("{}", "Too small!".red());
-                return (false, attempts);
-            },
-            Ordering::Greater => {
-                println!("{}", "Too big!".red());
-                return (false, attempts);
+        let closeness = closeness_pct(guess, secret, range);
+        let proximity = shows_proximity(round.trace().len() + 1, config);
+        let ordering = if (guess - secret).abs() <= config.tolerance {
+            Ordering::Equal
+        } else {
+            guess.partial_cmp(&secret).ok_or(GameError::NonFiniteComparison { guess, secret })?
+        };
+        match ordering {
+            Ordering::Less | Ordering::Greater => {
+                let too_small = ordering == Ordering::Less;
+                for line in miss_lines(round, guess, secret, too_small, closeness, config, proximity) {
+                    println!("{}", line);
+                }
+                round.record_guess(guess, if too_small { Verdict::TooSmall } else { Verdict::TooBig }, closeness);
+                // Proximity-only play hides the direction, so nothing narrows either
+                if !config.proximity_only {
+                    round.narrow_bounds(guess, too_small);
+                }
             },
             Ordering::Equal => {
+                round.record_guess(guess, Verdict::Correct, closeness);
+                if config.celebrate {
+                    celebrate(config);
+                }
                 println!("{}", "Correct! You guessed it!".green().bold());
-                return (true, attempts);
+                return Ok(true);
+            }
+        }
+
+        // Under a point budget, farther misses cost more and zero ends the round
+        if let Some(budget) = config.point_budget {
+            let (cost, left) = round.spend_points(closeness, budget);
+            println!("-{} point(s), {} left", cost, left);
+            if round.out_of_points {
+                println!("{}", "Out of points!".red().bold());
+                return Ok(false);
             }
         }
+
+        // Blind mode gives nothing beyond the direction
+        if config.blind {
+            return Ok(false);
+        }
+
+        let (closest, distance) = round.track_closest(guess, secret);
+        if proximity {
+            println!("Closest: {} (off by {})", format_number(closest), format_number(distance));
+        }
+
+        // Offer a free nudge to players stuck far from the answer
+        if round.track_far_guess(closeness) && config.mercy {
+            println!("{}", mercy_hint(secret, range).cyan());
+        }
+        return Ok(false);
+    }
+}
+
+/// Decorative "Attempt #N" header, spaced from the turn before
+/// None in compact mode, which prints only the prompt and the verdict
+fn attempt_header(attempt: u32, config: &GameConfig) -> Option<String> {
+    (!config.compact).then(|| format!("\nAttempt #{}", attempt))
+}
+
+/// Whether a guess gets proximity feedback, which may only be given every
+/// few guesses; only valid guesses count, so a typo doesn't shift the interval
+/// Parameters:
+///   guess_number: usize - this guess's place among the round's valid guesses (from 1)
+///   config: &GameConfig - proximity interval and blind mode
+fn shows_proximity(guess_number: usize, config: &GameConfig) -> bool {
+    !config.blind && guess_number.is_multiple_of(config.proximity_interval as usize)
+}
+
+/// Lists the round's guesses by how much each narrowed the range
+/// Proximity-only play never says which way a guess was off, so there
+/// is nothing to rank; blind play shows no numbers beyond the direction
+fn guess_usefulness_lines(round: &RoundState, range: (f64, f64), config: &GameConfig) -> Vec<String> {
+    if config.proximity_only {
+        return vec![format!("{}", "Guess ranking isn't available in proximity-only mode.".yellow())];
+    }
+    if config.blind {
+        return vec![format!("{}", "Guess ranking isn't available in blind mode.".yellow())];
+    }
+    let ranked = round.guess_usefulness(range);
+    if ranked.is_empty() {
+        return vec![String::from("No guesses yet.")];
+    }
+    let width = range.1 - range.0;
+    let mut lines = vec![String::from("Your guesses, by how much they narrowed the range:")];
+    for (i, (guess, cut)) in ranked.iter().enumerate() {
+        let share = if width > 0.0 { cut / width * 100.0 } else { 0.0 };
+        let label = match i {
+            0 if ranked.len() > 1 => " (most useful)",
+            i if i + 1 == ranked.len() && i > 0 => " (least useful)",
+            _ => "",
+        };
+        lines.push(format!("  {}. {} cut {} ({:.0}% of the range){}", i + 1, format_number(*guess), format_number(*cut), share, label));
+    }
+    lines
+}
+
+/// Points out a guess the player's own earlier guesses already rule out
+/// (never in blind mode, which shows no numbers beyond the direction)
+fn bounds_nudge(round: &RoundState, guess: f64, config: &GameConfig) -> Option<String> {
+    if !config.nudge || config.blind {
+        return None;
+    }
+    round.bounds_nudge(guess)
+}
+
+/// Warning for a repeated guess, reminding the player what it got before
+/// Proximity-only play never reveals which way a guess was off
+fn repeat_warning(guess: f64, verdict: Verdict, config: &GameConfig) -> String {
+    if config.proximity_only {
+        format!("You already guessed {}", format_number(guess))
+    } else {
+        format!("You already guessed {} (it was {})", format_number(guess), verdict.describe())
+    }
+}
+
+/// Warns about a repeated guess and decides whether it still counts
+/// Parameters:
+///   guess: f64 - the repeated value
+///   verdict: Verdict - what the earlier guess of it got
+///   config: &GameConfig - repeat policy and whether menus are shown
+/// Returns:
+///   true to count the guess, false to ask for another (scripted input
+///   is never asked, so Confirm counts the guess)
+fn confirm_repeat(guess: f64, verdict: Verdict, config: &GameConfig) -> Result<bool, GameError> {
+    println!("{}", repeat_warning(guess, verdict, config).yellow());
+    match config.repeat_guesses {
+        RepeatGuesses::Warn => Ok(true),
+        // Asking would swallow the next scripted guess as the answer
+        RepeatGuesses::Confirm if !config.interactive() || input::scripted() => Ok(true),
+        RepeatGuesses::Confirm => {
+            print!("Use it anyway? (y/N): ");
+            io::stdout().flush()?;
+            let answer = input::normalize_input(&input::read_line()?);
+            Ok(matches!(answer.to_lowercase().as_str(), "y" | "yes"))
+        },
+        RepeatGuesses::Reject => Ok(false),
+    }
+}
+
+/// Confetti frames shown by `celebrate`
+const CONFETTI_FRAMES: usize = 4;
+/// Characters in one confetti frame
+const CONFETTI_WIDTH: usize = 32;
+/// Pause between confetti frames
+const CONFETTI_PAUSE: Duration = Duration::from_millis(120);
+
+/// Prints a few frames of colored confetti
+/// Frames follow each other instantly with `--no-delay` or when the
+/// output is not a terminal
+fn celebrate(config: &GameConfig) {
+    let pause = confetti_pauses(config);
+    let mut rng = rand::rng();
+    for frame in 0..CONFETTI_FRAMES {
+        if pause && frame > 0 {
+            thread::sleep(CONFETTI_PAUSE);
+        }
+        let line: String = (0..CONFETTI_WIDTH)
+            .map(|_| {
+                let piece = ['*', '+', 'o', '~', '.', ' '][rng.random_range(0..6)].to_string();
+                match rng.random_range(0..4) {
+                    0 => piece.red(),
+                    1 => piece.yellow(),
+                    2 => piece.green(),
+                    _ => piece.magenta(),
+                }
+                .to_string()
+            })
+            .collect();
+        println!("{}", line);
+    }
+}
+
+/// Whether the confetti waits between frames
+fn confetti_pauses(config: &GameConfig) -> bool {
+    !config.demo_delay.is_zero() && io::stdout().is_terminal()
+}
+
+/// Free narrowing hint for players who keep missing by a mile
+/// Parameters:
+///   secret: f64 - target number
+///   range: (f64, f64) - current guessing range
+pub fn mercy_hint(secret: f64, range: (f64, f64)) -> String {
+    let midpoint = (range.0 + range.1) / 2.0;
+    let half = if secret < midpoint { "lower" } else { "upper" };
+    format!("You seem stuck — the number is in the {} half ({:.1} is the midpoint).", half, midpoint)
+}
+
+/// Formats a number without trailing decimals when it is whole
+pub fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+/// Renders the guess prompt from the configured template
+/// Parameters:
+///   config: &GameConfig - holds the prompt template
+///   start: f64 - range lower bound
+///   end: f64 - range upper bound
+pub fn guess_prompt(config: &GameConfig, start: f64, end: f64) -> String {
+    config.prompt.replace("{range}", &config.game_range(start, end).to_string())
+}
+
+/// Checks whether a wrong guess is near enough to deserve an extra cue
+/// Parameters:
+///   guess: f64 - the player's guess
+///   secret: f64 - target number
+///   alert_distance: Option<f64> - configured alert distance, if any
+pub fn is_close_call(guess: f64, secret: f64, alert_distance: Option<f64>) -> bool {
+    match alert_distance {
+        Some(distance) => guess != secret && (guess - secret).abs() <= distance,
+        None => false,
+    }
+}
+
+/// What a wrong guess is told, worked out before the guess joins the trace
+/// Normal play says which way it was off, tinted by closeness, and may add
+/// a close-call alert; blind mode, and turns without proximity feedback,
+/// get the bare direction. Proximity-only play never gives the direction,
+/// only whether the guess was warmer or colder than the last (nothing for
+/// the first guess)
+/// Parameters:
+///   round: &RoundState - guesses so far, for the warmer/colder trend
+///   guess: f64 - the wrong guess
+///   secret: f64 - target number
+///   too_small: bool - whether the guess was below the secret
+///   closeness: f64 - how close the guess was (0-100)
+///   config: &GameConfig - feedback settings
+///   proximity: bool - whether this guess gets proximity feedback
+/// Returns:
+///   Lines to print, in order
+fn miss_lines(round: &RoundState, guess: f64, secret: f64, too_small: bool, closeness: f64, config: &GameConfig, proximity: bool) -> Vec<String> {
+    if config.proximity_only {
+        return round.trend(guess, secret).map(|trend| tint_by_closeness(trend, closeness).to_string()).into_iter().collect();
+    }
+    let text = if too_small { "Too small!" } else { "Too big!" };
+    if !proximity {
+        return vec![String::from(text)];
+    }
+    let mut lines = vec![tint_by_closeness(text, closeness).to_string()];
+    if is_close_call(guess, secret, config.alert_distance) {
+        lines.push("So close!".yellow().bold().to_string());
+    }
+    lines
+}
+
+/// How close a guess is to the secret, relative to the range width
+/// Returns:
+///   100.0 for an exact hit, falling toward 0.0 at the far end of the range
+pub fn closeness_pct(guess: f64, secret: f64, range: (f64, f64)) -> f64 {
+    let span = range.1 - range.0;
+    if span <= 0.0 {
+        return 100.0;
+    }
+    (100.0 - (guess - secret).abs() / span * 100.0).clamp(0.0, 100.0)
+}
+
+/// Interpolates from blue (far) to red (near)
+/// Parameters:
+///   closeness_pct: f64 - closeness percentage, clamped to 0-100
+/// Returns:
+///   RGB triple for 24-bit terminals
+pub fn proximity_color(closeness_pct: f64) -> (u8, u8, u8) {
+    let t = if closeness_pct.is_nan() { 0.0 } else { closeness_pct.clamp(0.0, 100.0) / 100.0 };
+    let red = (255.0 * t).round() as u8;
+    let blue = (255.0 * (1.0 - t)).round() as u8;
+    (red, 0, blue)
+}
+
+/// Whether the terminal advertises 24-bit color support
+fn supports_truecolor() -> bool {
+    matches!(std::env::var("COLORTERM").as_deref(), Ok("truecolor") | Ok("24bit"))
+}
+
+/// Colors feedback text by closeness, falling back to plain red
+/// when the terminal cannot show truecolor
+fn tint_by_closeness(text: &str, closeness_pct: f64) -> colored::ColoredString {
+    if supports_truecolor() {
+        let (r, g, b) = proximity_color(closeness_pct);
+        text.truecolor(r, g, b)
+    } else {
+        text.red()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripted_repeat_is_counted_without_reading_a_confirmation() {
+        input::use_script(vec![String::from("7")]);
+        let config = GameConfig { interactive: Some(true), repeat_guesses: RepeatGuesses::Confirm, ..GameConfig::default() };
+        assert!(confirm_repeat(5.0, Verdict::TooSmall, &config).unwrap());
+        // The next scripted guess is still there
+        assert_eq!(input::read_line().unwrap(), "7");
+    }
+
+    #[test]
+    fn repeat_warning_recalls_the_earlier_verdict() {
+        let config = GameConfig::default();
+        assert_eq!(repeat_warning(60.0, Verdict::TooBig, &config), "You already guessed 60 (it was too big)");
+        let proximity_only = GameConfig { proximity_only: true, ..GameConfig::default() };
+        assert!(!repeat_warning(60.0, Verdict::TooBig, &proximity_only).contains("too big"));
+    }
+
+    #[test]
+    fn scripts_do_not_leak_between_threads() {
+        input::use_script(vec![String::from("1")]);
+        assert!(!thread::spawn(input::scripted).join().unwrap());
+        input::use_script(vec![String::from("2")]);
+        assert_eq!(input::read_line().unwrap(), "2");
+        assert!(input::read_line().is_err());
+    }
+
+    #[test]
+    fn blind_mode_shows_no_bound_values() {
+        let mut round = RoundState::new(None, 10);
+        round.record_guess(40.0, Verdict::TooSmall, 80.0);
+        round.narrow_bounds(40.0, true);
+        round.record_guess(70.0, Verdict::TooBig, 85.0);
+        round.narrow_bounds(70.0, false);
+        let sighted = GameConfig::default();
+        let blind = GameConfig { blind: true, ..GameConfig::default() };
+
+        assert!(bounds_nudge(&round, 30.0, &sighted).is_some_and(|nudge| nudge.contains("40")));
+        assert!(guess_usefulness_lines(&round, (1.0, 100.0), &sighted).iter().any(|line| line.contains("40")));
+
+        assert_eq!(bounds_nudge(&round, 30.0, &blind), None);
+        assert_eq!(bounds_nudge(&round, 90.0, &blind), None);
+        let lines = guess_usefulness_lines(&round, (1.0, 100.0), &blind);
+        assert!(lines.iter().all(|line| !line.contains("40") && !line.contains("70")));
+    }
+
+    #[test]
+    fn close_call_triggers_just_inside_the_alert_distance() {
+        assert!(is_close_call(47.0, 50.0, Some(3.0)));
+        assert!(is_close_call(52.5, 50.0, Some(3.0)));
+        assert!(!is_close_call(46.9, 50.0, Some(3.0)));
+        assert!(!is_close_call(53.5, 50.0, Some(3.0)));
+    }
+
+    #[test]
+    fn close_call_never_triggers_on_a_win_or_without_a_distance() {
+        assert!(!is_close_call(50.0, 50.0, Some(3.0)));
+        assert!(!is_close_call(49.0, 50.0, None));
+    }
+
+    #[test]
+    fn exact_decimal_game_gets_a_winnable_tolerance() {
+        let mut config = GameConfig { mode: GameMode::Decimal, tolerance: 0.0, ..GameConfig::default() };
+        assert!(make_winnable(&mut config, (0.0, 50.0)));
+        assert_eq!(config.tolerance, 0.5);
+        // The solver can now finish a decimal game
+        let secret = gen_secret(&mut seeded_rng(3), 0.0, 50.0, &config);
+        assert!(auto_solve(secret, (0.0, 50.0), &config).len() < 20);
+
+        let mut integer = GameConfig { mode: GameMode::Integer, tolerance: 0.0, ..GameConfig::default() };
+        assert!(!make_winnable(&mut integer, (0.0, 50.0)));
+        assert_eq!(integer.tolerance, 0.0);
+        let mut rounded = GameConfig { mode: GameMode::Decimal, tolerance: 0.0, secret_decimals: Some(1), ..GameConfig::default() };
+        assert!(!make_winnable(&mut rounded, (0.0, 50.0)));
+    }
+
+    #[test]
+    fn perfect_play_counts_bisection_steps() {
+        assert_eq!(perfect_play_guesses(2.0), Some(1));
+        assert_eq!(perfect_play_guesses(8.0), Some(3));
+        assert_eq!(perfect_play_guesses(1.0), None);
+    }
+
+    #[test]
+    fn perfect_play_is_a_premium_hint() {
+        assert!(HintChoice::PerfectPlay.cost() > HintChoice::Easy.cost());
+        assert!(HintChoice::PerfectPlay.menu_line().ends_with("cost 3)"));
+    }
+
+    #[test]
+    fn hint_summary_counts_each_category() {
+        let config = GameConfig::default();
+        let mut round = RoundState::new(None, 10);
+        assert_eq!(round.hint_summary(), "No hints used — nice!");
+        let mut rng = seeded_rng(5);
+        for choice in [HintChoice::Easy, HintChoice::Hard, HintChoice::Easy] {
+            choose_hint(choice, 42.0, &mut round, (1.0, 100.0), &config, &mut rng);
+        }
+        assert_eq!(round.hint_summary(), "You used 2 easy and 1 hard hint this round.");
+        choose_hint(HintChoice::Hard, 42.0, &mut round, (1.0, 100.0), &config, &mut rng);
+        assert_eq!(round.hint_summary(), "You used 2 easy and 2 hard hints this round.");
+    }
+
+    #[test]
+    fn proximity_color_runs_from_blue_to_red() {
+        assert_eq!(proximity_color(0.0), (0, 0, 255));
+        assert_eq!(proximity_color(50.0), (128, 0, 128));
+        assert_eq!(proximity_color(100.0), (255, 0, 0));
+        // Out-of-range percentages are clamped
+        assert_eq!(proximity_color(-20.0), proximity_color(0.0));
+        assert_eq!(proximity_color(250.0), proximity_color(100.0));
+        assert_eq!(proximity_color(f64::NAN), proximity_color(0.0));
+    }
+
+    #[test]
+    fn quartile_finds_each_quarter() {
+        assert_eq!(quartile(10.0, (0.0, 100.0)), (1, 0.0, 25.0));
+        assert_eq!(quartile(30.0, (0.0, 100.0)), (2, 25.0, 50.0));
+        assert_eq!(quartile(60.0, (0.0, 100.0)), (3, 50.0, 75.0));
+        assert_eq!(quartile(90.0, (0.0, 100.0)), (4, 75.0, 100.0));
+        // Custom bounds
+        assert_eq!(quartile(-5.0, (-20.0, 20.0)), (2, -10.0, 0.0));
+    }
+
+    #[test]
+    fn quartile_boundary_goes_to_the_upper_quarter() {
+        assert_eq!(quartile(50.0, (0.0, 100.0)).0, 3);
+        assert_eq!(quartile(25.0, (0.0, 100.0)).0, 2);
+        assert_eq!(quartile(0.0, (0.0, 100.0)).0, 1);
+        assert_eq!(quartile(100.0, (0.0, 100.0)).0, 4);
+    }
+
+    #[test]
+    fn focus_hint_uses_the_narrowed_bounds() {
+        let mut round = RoundState::new(None, 10);
+        round.record_guess(40.0, Verdict::TooSmall, 70.0);
+        round.narrow_bounds(40.0, true);
+        round.record_guess(60.0, Verdict::TooBig, 90.0);
+        round.narrow_bounds(60.0, false);
+        let (low, high) = round.known_bounds((1.0, 100.0));
+        assert_eq!(focus_third(55.0, (low, high)), "upper");
+        assert_eq!(focus_third(45.0, (40.0, 60.0)), "lower");
+        assert_eq!(focus_third(50.0, (40.0, 60.0)), "middle");
+    }
+
+    #[test]
+    fn focus_hint_needs_a_guess_first() {
+        let mut round = RoundState::new(None, 10);
+        choose_hint(HintChoice::Focus, 55.0, &mut round, (1.0, 100.0), &GameConfig::default(), &mut seeded_rng(1));
+        assert_eq!(round.hint_count(), 0);
+        assert_eq!(round.hints_used, 0);
+    }
+
+    #[test]
+    fn timeout_reveals_what_was_configured() {
+        let lines: Vec<Option<String>> =
+            TimeoutReveal::ALL.iter().map(|&reveal| timeout_reveal_line(62.0, (0.0, 100.0), reveal)).collect();
+        assert_eq!(
+            lines,
+            [
+                None,
+                Some(String::from("It was in the third quarter: between 50 and 75.")),
+                Some(String::from("The number was 62.00.")),
+            ]
+        );
+    }
+
+    #[test]
+    fn round_stops_cleanly_at_the_attempt_ceiling() {
+        let config = GameConfig { attempt_ceiling: 3, ..GameConfig::default() };
+        let mut round = RoundState::new(None, config.attempt_ceiling);
+        input::use_script(["10", "20", "30", "40"].map(String::from).to_vec());
+        let result = loop {
+            match game_loop(50.0, &mut round, (1.0, 100.0), &config) {
+                Ok(false) => continue,
+                other => break other,
+            }
+        };
+        assert!(matches!(result, Err(GameError::AttemptCeiling(3))));
+        assert_eq!(round.attempts, 3);
+        // The guess past the ceiling was never read
+        assert_eq!(input::read_line().unwrap(), "40");
+    }
+
+    #[test]
+    fn attempt_counter_never_overflows() {
+        let config = GameConfig { attempt_ceiling: u32::MAX, ..GameConfig::default() };
+        let mut round = RoundState::new(None, 10);
+        round.attempts = u32::MAX - 1;
+        input::use_script(vec![String::from("10"), String::from("20")]);
+        assert!(!game_loop(50.0, &mut round, (1.0, 100.0), &config).unwrap());
+        assert_eq!(round.attempts, u32::MAX);
+        assert!(matches!(game_loop(50.0, &mut round, (1.0, 100.0), &config), Err(GameError::AttemptCeiling(u32::MAX))));
+    }
+
+    #[test]
+    fn proximity_shows_every_third_valid_guess() {
+        let config = GameConfig { proximity_interval: 3, ..GameConfig::default() };
+        let shown: Vec<usize> = (1..=7).filter(|&n| shows_proximity(n, &config)).collect();
+        assert_eq!(shown, [3, 6]);
+        let every = GameConfig::default();
+        assert!((1..=4).all(|n| shows_proximity(n, &every)));
+    }
+
+    #[test]
+    fn typos_do_not_shift_the_proximity_interval() {
+        let config = GameConfig { proximity_interval: 3, ..GameConfig::default() };
+        let mut round = RoundState::new(None, 10);
+        input::use_script(["10", "oops", "500", "20"].map(String::from).to_vec());
+        game_loop(50.0, &mut round, (1.0, 100.0), &config).unwrap();
+        game_loop(50.0, &mut round, (1.0, 100.0), &config).unwrap();
+        // Two typos cost attempts, but the next guess is still the third valid one
+        assert_eq!((round.attempts, round.trace().len()), (4, 2));
+        assert!(shows_proximity(round.trace().len() + 1, &config));
+    }
+
+    #[test]
+    fn dice_sums_peak_in_the_middle() {
+        let dice = DiceRange { count: 3, sides: 6 };
+        let range = GameRange { start: dice.min(), end: dice.max(), start_inclusive: true, end_inclusive: true };
+        let mut rng = seeded_rng(3);
+        let mut counts = [0u32; 19];
+        for _ in 0..20_000 {
+            let sum = gen_rand(&mut rng, range, Distribution::Dice(dice), None);
+            assert!((3.0..=18.0).contains(&sum), "{}", sum);
+            counts[sum as usize] += 1;
+        }
+        let middle = counts[10].min(counts[11]);
+        for (sum, &count) in counts.iter().enumerate().filter(|&(sum, _)| sum != 10 && sum != 11) {
+            assert!(count < middle, "{} came up {} times, more than 10/11 ({})", sum, count, middle);
+        }
+    }
+
+    #[test]
+    fn custom_prompt_shows_the_range() {
+        let config = GameConfig { prompt: String::from("Your number? {range} > "), ..GameConfig::default() };
+        assert_eq!(guess_prompt(&config, 1.0, 50.0), "Your number? 1-50 > ");
+        assert_eq!(guess_prompt(&GameConfig::default(), 1.0, 100.0), "Enter your guess (1-100): ");
+    }
+
+    /// The value a hint line shows after its " = "
+    fn shown_value(line: &str) -> f64 {
+        let value = line.rsplit(" = ").next().unwrap();
+        value.split(" (").next().unwrap().parse().unwrap()
+    }
+
+    #[test]
+    fn hint_scale_multiplies_the_shown_value() {
+        for seed in 0..10 {
+            let plain = easy_hint_chooser(30.0, Some(&mut HintDeck::new(seed)), 1.0);
+            let scaled = easy_hint_chooser(30.0, Some(&mut HintDeck::new(seed)), 10.0);
+            assert!(!plain.contains("scaled"));
+            assert!(scaled.ends_with(" (scaled ×10)"), "{}", scaled);
+            assert!((shown_value(&scaled) - shown_value(&plain) * 10.0).abs() < 0.1, "{} vs {}", plain, scaled);
+
+            let plain = hard_hint_chooser(3.0, Some(&mut HintDeck::new(seed)), false, 1.0, f64::MAX);
+            let scaled = hard_hint_chooser(3.0, Some(&mut HintDeck::new(seed)), false, 10.0, f64::MAX);
+            assert!((shown_value(&scaled[0]) - shown_value(&plain[0]) * 10.0).abs() < 0.1, "{:?} vs {:?}", plain, scaled);
+        }
+    }
+
+    #[test]
+    fn hint_previews_cover_every_category_for_free() {
+        let previews = hint_previews((1.0, 100.0), 1.0);
+        assert_eq!(previews.len(), 4);
+        assert!(previews[0].contains("EXAMPLE — not the real number"));
+        for (line, label) in previews[1..].iter().zip(["Easy Hint", "Hard Hint", "Quartile Hint"]) {
+            assert!(line.contains(label), "{}", line);
+        }
+        // The placeholder secret, never the real one, is what the examples use
+        assert!(previews[0].contains("pretend it is 42"));
+
+        let config = GameConfig { hint_budget: Some(2), ..GameConfig::default() };
+        let mut round = RoundState::new(None, 0);
+        choose_hint(HintChoice::Preview, 77.0, &mut round, (1.0, 100.0), &config, &mut seeded_rng(1));
+        assert_eq!(round.hints_used, 0);
+        assert_eq!(round.hint_count(), 0);
+    }
+
+    #[test]
+    fn dump_hints_lists_every_template_for_a_benign_secret() {
+        let config = crate::config::parse_args(["--dump-hints", "--secret", "10"].map(String::from)).unwrap();
+        assert!(config.dump_hints);
+        let secret = config.dump_secret.unwrap();
+
+        let lines = dump_hints(secret);
+        let (easy, hard) = (easy_hints().len(), hard_hints().len());
+        assert_eq!(lines.len(), easy + hard + 2);
+        assert_eq!(lines[0], format!("Easy hints ({}):", easy));
+        assert_eq!(lines[easy + 1], format!("Hard hints ({}):", hard));
+        assert!(lines.iter().all(|line| !line.contains("non-finite")));
+    }
+
+    #[test]
+    fn dump_hints_flags_non_finite_values() {
+        assert!(dump_hints(f64::INFINITY).iter().any(|line| line.contains("non-finite")));
+    }
+
+    #[test]
+    fn hard_hints_stay_under_the_magnitude_guard() {
+        let max_value = GameConfig::default().max_hint_value;
+        for secret in [20.0, 60.0, 9_500.0] {
+            for seed in 0..20 {
+                let lines = hard_hint_chooser(secret, Some(&mut HintDeck::new(seed)), false, 1.0, max_value);
+                let hint = lines.last().unwrap();
+                assert!(shown_value(hint).abs() <= max_value, "S = {}: {}", secret, hint);
+            }
+        }
+        // Nothing under the guard: an easy hint stands in
+        let lines = hard_hint_chooser(9_500.0, None, false, 1.0, 10.0);
+        assert!(lines[0].contains("too big to read") && lines[1].contains("Easy Hint"), "{:?}", lines);
+    }
+
+    #[test]
+    fn surrender_breakdown_explains_the_secret() {
+        let config = GameConfig { mode: GameMode::Integer, ..GameConfig::default() };
+        assert_eq!(
+            reveal_breakdown(37.0, (0.0, 100.0), &config),
+            [
+                "It was in the second quarter (25–50).",
+                "It was odd.",
+                "An optimal first guess was 50; halving from there finds it in 3 guess(es).",
+            ]
+        );
+
+        let config = GameConfig { mode: GameMode::Decimal, tolerance: 0.5, ..GameConfig::default() };
+        let lines = reveal_breakdown(80.0, (0.0, 100.0), &config);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("fourth quarter"));
+        assert!(!lines.iter().any(|line| line.contains("even") || line.contains("odd")));
+    }
+
+    #[test]
+    fn menu_lines_show_power_and_cost() {
+        assert_eq!(HintChoice::Easy.menu_line(), "1. Easy hint (reveals ~1 easy equation, cost 1)");
+        assert_eq!(HintChoice::Hard.menu_line(), "2. Hard hint (reveals ~1 hard equation, cost 1)");
+        assert_eq!(
+            HintChoice::PerfectPlay.menu_line(),
+            "4. Perfect-play hint - how many guesses you still need (narrows the range, cost 3)"
+        );
+        for choice in HintChoice::MENU {
+            let line = choice.menu_line();
+            match choice.category() {
+                Some(category) => assert!(line.ends_with(&format!("({}, cost {})", category.power(), choice.cost())), "{}", line),
+                None => assert!(!line.contains("cost"), "{}", line),
+            }
+        }
+    }
+
+    #[test]
+    fn narration_follows_the_shrinking_interval() {
+        let config = GameConfig { mode: GameMode::Integer, ..GameConfig::default() };
+        let steps = auto_solve_steps(37.0, (1.0, 100.0), &config);
+        let narration = narrate_steps(&steps, 37.0, config.tolerance);
+        let expected = [
+            ("The range is 1–100, so I'll guess the midpoint 50 to halve it.", "Too big → the answer is 1–49"),
+            ("The range is 1–49, so I'll guess the midpoint 25 to halve it.", "Too small → the answer is 26–49"),
+            ("The range is 26–49, so I'll guess the midpoint 37 to halve it.", "Correct → solved!"),
+        ];
+        assert_eq!(narration.len(), expected.len());
+        for ((reasoning, reaction), (want_reasoning, want_reaction)) in narration.iter().zip(expected) {
+            assert_eq!(reasoning, want_reasoning);
+            assert_eq!(reaction, want_reaction);
+        }
+    }
+
+    #[test]
+    fn proximity_only_gives_trends_but_never_the_direction() {
+        let config = GameConfig { proximity_only: true, ..GameConfig::default() };
+        let secret = 45.0;
+        let mut round = RoundState::new(None, 0);
+        let mut output = Vec::new();
+        for guess in [20.0, 40.0, 70.0, 50.0] {
+            let closeness = closeness_pct(guess, secret, (1.0, 100.0));
+            let too_small = guess < secret;
+            output.push(miss_lines(&round, guess, secret, too_small, closeness, &config, true));
+            round.record_guess(guess, if too_small { Verdict::TooSmall } else { Verdict::TooBig }, closeness);
+        }
+
+        assert!(output[0].is_empty(), "{:?}", output[0]);
+        let text = output.concat().join("\n");
+        assert!(text.contains("Warmer") && text.contains("Colder"), "{}", text);
+        assert!(!text.contains("Too small") && !text.contains("Too big"), "{}", text);
+
+        let normal = miss_lines(&round, 30.0, secret, true, 85.0, &GameConfig::default(), true);
+        assert!(normal[0].contains("Too small!"));
+    }
+
+    #[test]
+    fn rounded_secrets_have_at_most_one_decimal() {
+        let config = GameConfig { mode: GameMode::Decimal, tolerance: 0.0, secret_decimals: Some(1), ..GameConfig::default() };
+        let mut rng = seeded_rng(11);
+        for _ in 0..1_000 {
+            let secret = gen_secret(&mut rng, 1.0, 100.0, &config);
+            assert!((1.0..=100.0).contains(&secret));
+            let text = format!("{}", secret);
+            assert!(text.split_once('.').is_none_or(|(_, fraction)| fraction.len() <= 1), "{}", text);
+        }
+    }
+
+    #[test]
+    fn usefulness_lines_label_the_most_and_least_useful() {
+        let mut round = RoundState::new(None, 0);
+        round.record_guess(50.0, Verdict::TooSmall, 87.0);
+        round.record_guess(90.0, Verdict::TooBig, 73.0);
+        round.record_guess(70.0, Verdict::TooBig, 93.0);
+        let lines = guess_usefulness_lines(&round, (1.0, 100.0), &GameConfig::default());
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].starts_with("  1. 50 cut 49") && lines[1].ends_with("(most useful)"), "{}", lines[1]);
+        assert!(lines[2].starts_with("  2. 70 cut 20") && !lines[2].contains("useful)"), "{}", lines[2]);
+        assert!(lines[3].starts_with("  3. 90 cut 10") && lines[3].ends_with("(least useful)"), "{}", lines[3]);
+    }
+
+    #[test]
+    fn celebration_without_delay_never_waits() {
+        let config = crate::config::parse_args(["--celebrate", "--no-delay"].map(String::from)).unwrap();
+        assert!(config.celebrate);
+        assert!(!confetti_pauses(&config));
+
+        let mut round = RoundState::new(None, config.attempt_ceiling);
+        input::use_script(vec![String::from("42")]);
+        let started = std::time::Instant::now();
+        assert!(game_loop(42.0, &mut round, (1.0, 100.0), &config).unwrap());
+        assert!(started.elapsed() < CONFETTI_PAUSE);
+        assert_eq!(round.trace().last().map(|event| event.verdict), Some(Verdict::Correct));
+    }
+
+    #[test]
+    fn bell_rings_only_for_a_win_with_the_flag() {
+        let with_bell = crate::config::parse_args([String::from("--bell")]).unwrap();
+        assert_eq!(win_bell(true, with_bell.bell, true), "\x07");
+        assert_eq!(win_bell(true, GameConfig::default().bell, true), "");
+        assert_eq!(win_bell(false, true, true), "");
+        // Piped output never gets the control byte
+        assert_eq!(win_bell(true, true, false), "");
+    }
+
+    #[test]
+    fn compact_mode_drops_the_attempt_headers() {
+        let header_lines = |config: &GameConfig| {
+            let mut round = RoundState::new(None, config.attempt_ceiling);
+            input::use_script(["10", "90", "50"].map(String::from).to_vec());
+            let mut lines = 0;
+            loop {
+                lines += attempt_header(round.attempts + 1, config).map_or(0, |header| header.lines().count());
+                if game_loop(50.0, &mut round, (1.0, 100.0), config).unwrap() {
+                    break lines;
+                }
+            }
+        };
+        assert_eq!(header_lines(&GameConfig::default()), 6);
+        assert_eq!(header_lines(&GameConfig { compact: true, ..GameConfig::default() }), 0);
+        assert_eq!(attempt_header(2, &GameConfig::default()).as_deref(), Some("\nAttempt #2"));
     }
 }